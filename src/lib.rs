@@ -19,12 +19,31 @@ extern crate random_integer;
 #[macro_use]
 extern crate bitflags;
 
+#[cfg(feature = "log")]
+#[macro_use]
+extern crate log;
+
+/* without the log feature, log messages are still type-checked but discarded */
+#[cfg(not(feature = "log"))]
+macro_rules! debug {
+    ($($arg:tt)*) => { if false { let _ = format!($($arg)*); } };
+}
+#[cfg(not(feature = "log"))]
+macro_rules! trace {
+    ($($arg:tt)*) => { if false { let _ = format!($($arg)*); } };
+}
+#[cfg(not(feature = "log"))]
+macro_rules! error {
+    ($($arg:tt)*) => { if false { let _ = format!($($arg)*); } };
+}
+
 use std::io::prelude::*;
-use std::net::{TcpStream};
+use std::net::{TcpStream, ToSocketAddrs};
 use ssh2::Session;
 use regex::Regex;
 use std::time::SystemTime;
 
+#[derive(Copy, Clone, PartialEq, Eq, Hash)]
 /// MAC Address
 pub struct MacAddress {
     pub bytes: [u8; 6],
@@ -42,15 +61,26 @@ impl Default for MacAddress {
 impl std::str::FromStr for MacAddress {
     type Err = std::io::Error;
 
+    /// Parse "aa:bb:cc:dd:ee:ff", "aa-bb-cc-dd-ee-ff" or "aabb.ccdd.eeff"
     fn from_str (s: &str) -> Result<MacAddress, std::io::Error> {
-        let split: std::vec::Vec<&str> = s.split(":").collect();
+        let split: std::vec::Vec<&str> = if s.contains('.') {
+            let groups: std::vec::Vec<&str> = s.split(".").collect();
+            if groups.len() != 3 || groups.iter().any(|x| x.len() != 4 || !x.is_char_boundary(2)) {
+                return Err(std::io::Error::new(std::io::ErrorKind::InvalidData, "Received invalid data"))
+            }
+            groups.iter().flat_map(|x| vec![&x[..2], &x[2..]]).collect()
+        } else if s.contains('-') {
+            s.split("-").collect()
+        } else {
+            s.split(":").collect()
+        };
         let mut bytes: [u8; 6] = [0; 6];
         let mut pos: usize = 0;
         if split.len() != 6 {
             return Err(std::io::Error::new(std::io::ErrorKind::InvalidData, "Received invalid data"))
         }
         for strbyte in split {
-            if strbyte.len() != 2 {
+            if strbyte.len() != 2 || !strbyte.chars().all(|c| c.is_ascii_hexdigit()) {
                 return Err(std::io::Error::new(std::io::ErrorKind::InvalidData, "Received invalid data"))
             }
             bytes[pos] = match u8::from_str_radix(strbyte, 16) {
@@ -113,6 +143,49 @@ impl std::str::FromStr for IPv4Address {
     }
 }
 
+impl IPv4Address {
+    /// Netmask for a prefix length, e.g. 24 -> 255.255.255.0
+    pub fn netmask(prefix_len: u8) -> std::io::Result<IPv4Address> {
+        if prefix_len > 32 {
+            return Err(std::io::Error::new(std::io::ErrorKind::InvalidInput, "Invalid prefix length"));
+        }
+        let mask = (!0u64 << (32 - prefix_len)) as u32;
+        Ok(IPv4Address { bytes: mask.to_be_bytes() })
+    }
+
+    /// Prefix length of a netmask, e.g. 255.255.255.0 -> 24 (None for non-contiguous masks)
+    pub fn prefix_len(&self) -> Option<u8> {
+        let mask = u32::from_be_bytes(self.bytes);
+        let len = mask.leading_ones();
+        if mask.checked_shl(len).unwrap_or(0) != 0 {
+            return None;
+        }
+        Some(len as u8)
+    }
+
+    /// Check if the address is a contiguous netmask
+    pub fn is_valid_netmask(&self) -> bool {
+        self.prefix_len().is_some()
+    }
+
+    /// Convert to the standard library address type
+    pub fn to_std(&self) -> std::net::Ipv4Addr {
+        std::net::Ipv4Addr::from(self.bytes)
+    }
+}
+
+impl From<std::net::Ipv4Addr> for IPv4Address {
+    fn from(addr: std::net::Ipv4Addr) -> IPv4Address {
+        IPv4Address { bytes: addr.octets() }
+    }
+}
+
+impl From<IPv4Address> for std::net::Ipv4Addr {
+    fn from(addr: IPv4Address) -> std::net::Ipv4Addr {
+        addr.to_std()
+    }
+}
+
 impl std::fmt::Display for IPv4Address {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         write!(f, "{}.{}.{}.{}", self.bytes[0], self.bytes[1], self.bytes[2], self.bytes[3])
@@ -125,14 +198,591 @@ impl std::fmt::Debug for IPv4Address {
     }
 }
 
+#[derive(Debug, Clone, Default)]
+/// Firmware or boot loader version, e.g. "V2.70(AAHL.3)" or "V2.00". Versions
+/// are ordered by major, minor and build number and the release date in raw
+/// is ignored. Versions in an unknown format (only raw is set) sort before
+/// all others and are only equal if their raw strings are.
+pub struct FirmwareVersion {
+    /// Major version, e.g. 2
+    pub major: u16,
+    /// Minor version, e.g. 70
+    pub minor: u16,
+    /// Zyxel model code, e.g. "AAHL" (empty if not part of the version)
+    pub model_code: String,
+    /// Build number, e.g. 3 (0 if not part of the version)
+    pub build: u16,
+    /// Version string as reported by the switch (including the release date)
+    pub raw: String,
+}
+
+impl FirmwareVersion {
+    /// Only raw is set, see parse_basic_info
+    fn is_unknown(&self) -> bool {
+        self.major == 0 && self.minor == 0 && self.build == 0 && self.model_code.is_empty()
+    }
+
+    fn key(&self) -> (u16, u16, u16, &str, &str) {
+        let raw = if self.is_unknown() { self.raw.as_str() } else { "" };
+        (self.major, self.minor, self.build, self.model_code.as_str(), raw)
+    }
+}
+
+impl PartialEq for FirmwareVersion {
+    fn eq(&self, other: &FirmwareVersion) -> bool {
+        self.key() == other.key()
+    }
+}
+
+impl Eq for FirmwareVersion {}
+
+impl PartialOrd for FirmwareVersion {
+    fn partial_cmp(&self, other: &FirmwareVersion) -> Option<std::cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for FirmwareVersion {
+    fn cmp(&self, other: &FirmwareVersion) -> std::cmp::Ordering {
+        self.key().cmp(&other.key())
+    }
+}
+
+impl std::str::FromStr for FirmwareVersion {
+    type Err = std::io::Error;
+
+    /// Parse "V<major>.<minor>(<model code>.<build>)" or "V<major>.<minor>",
+    /// optionally followed by " | <date>"
+    fn from_str (s: &str) -> Result<FirmwareVersion, std::io::Error> {
+        lazy_static! {
+            static ref RE: Regex = Regex::new(r"^V(\d+)\.(\d+)(?:\(([A-Z0-9]+)\.(\d+)\))?(?:\s|$)").unwrap();
+        }
+
+        let raw = s.trim();
+        let cap = match RE.captures(raw) {
+            Some(x) => x,
+            None => { return Err(std::io::Error::new(std::io::ErrorKind::InvalidData, "Received invalid firmware version")) },
+        };
+        let num = |i: usize| match cap.get(i) {
+            Some(x) => x.as_str().parse::<u16>().map_err(|_e| std::io::Error::new(std::io::ErrorKind::InvalidData, "Received invalid firmware version")),
+            None => Ok(0),
+        };
+
+        Ok(FirmwareVersion {
+            major: num(1)?,
+            minor: num(2)?,
+            model_code: cap.get(3).map(|x| x.as_str().to_string()).unwrap_or_default(),
+            build: num(4)?,
+            raw: raw.to_string(),
+        })
+    }
+}
+
+impl std::fmt::Display for FirmwareVersion {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if self.is_unknown() {
+            return write!(f, "{}", self.raw);
+        }
+        /* Zyxel always uses two digits for the minor version ("V2.00") */
+        write!(f, "V{}.{:02}", self.major, self.minor)?;
+        if !self.model_code.is_empty() {
+            write!(f, "({}.{})", self.model_code, self.build)?;
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug)]
+/// Errors returned by the GS1900 methods
+pub enum GS1900Error {
+    /// SSH protocol or authentication failure
+    Ssh(ssh2::Error),
+    /// I/O failure of the underlying connection
+    Io(std::io::Error),
+    /// The switch returned data which could not be parsed
+    Parse { context: String, line: String },
+    /// The switch did not show the expected prompt after login
+    UnexpectedPrompt,
+    /// The switch rejected the command (e.g. "% Invalid input detected at '^' marker")
+    CommandRejected { message: String },
+    /// The firmware does not support the requested feature
+    Unsupported { feature: String },
+    /// The operation did not finish in time
+    Timeout { operation: String },
+    /// The command output ended before the prompt appeared or its last table row is incomplete
+    Truncated { command: String, got_rows: usize },
+    /// Configuration requires privileged mode, see GS1900::enable()
+    NotPrivileged,
+    /// The connection was opened read-only, see ConnectionConfig::read_only
+    ReadOnly,
+    /// The operation needs the password, but the connection uses SSH key authentication
+    PasswordRequired,
+}
+
+impl std::fmt::Display for GS1900Error {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            GS1900Error::Ssh(e) => write!(f, "SSH error: {}", e),
+            GS1900Error::Io(e) => write!(f, "I/O error: {}", e),
+            GS1900Error::Parse { context, line } if line.is_empty() => write!(f, "Parse error: {}", context),
+            GS1900Error::Parse { context, line } => write!(f, "Parse error: {} in '{}'", context, line),
+            GS1900Error::UnexpectedPrompt => write!(f, "Unexpected prompt"),
+            GS1900Error::CommandRejected { message } => write!(f, "Command rejected: {}", message),
+            GS1900Error::Unsupported { feature } => write!(f, "Not supported by the firmware: {}", feature),
+            GS1900Error::Timeout { operation } => write!(f, "Timeout: {}", operation),
+            GS1900Error::Truncated { command, got_rows } => write!(f, "Truncated output of '{}' after {} rows", command, got_rows),
+            GS1900Error::NotPrivileged => write!(f, "Not in privileged mode"),
+            GS1900Error::ReadOnly => write!(f, "Connection is read-only"),
+            GS1900Error::PasswordRequired => write!(f, "Password required"),
+        }
+    }
+}
+
+impl std::error::Error for GS1900Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            GS1900Error::Ssh(e) => Some(e),
+            GS1900Error::Io(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+impl From<GS1900Error> for std::io::Error {
+    fn from(e: GS1900Error) -> std::io::Error {
+        match e {
+            GS1900Error::Io(e) => e,
+            GS1900Error::Ssh(e) => e.into(),
+            GS1900Error::Parse { .. } => std::io::Error::new(std::io::ErrorKind::InvalidData, e),
+            GS1900Error::Timeout { .. } => std::io::Error::new(std::io::ErrorKind::TimedOut, e),
+            _ => std::io::Error::other(e),
+        }
+    }
+}
+
+impl From<std::io::Error> for GS1900Error {
+    /// Unwraps GS1900 errors carried in an I/O error, parse helpers
+    /// (FromStr implementations) report InvalidData
+    fn from(e: std::io::Error) -> GS1900Error {
+        if GS1900Error::from_io(&e).is_some() {
+            /* checked by from_io, cannot fail */
+            return *e.into_inner().unwrap().downcast::<GS1900Error>().unwrap();
+        }
+
+        match e.kind() {
+            std::io::ErrorKind::InvalidData => GS1900Error::Parse { context: e.to_string(), line: String::new() },
+            _ => GS1900Error::Io(e),
+        }
+    }
+}
+
+impl From<ssh2::Error> for GS1900Error {
+    fn from(e: ssh2::Error) -> GS1900Error {
+        GS1900Error::Ssh(e)
+    }
+}
+
+impl GS1900Error {
+    /// Get the GS1900 specific error wrapped in an I/O error
+    pub fn from_io(e: &std::io::Error) -> Option<&GS1900Error> {
+        e.get_ref().and_then(|x| x.downcast_ref::<GS1900Error>())
+    }
+
+    /// Parse error for a line of command output
+    fn parse<C: std::fmt::Display, L: OutputLine + ?Sized>(context: C, line: &L) -> GS1900Error {
+        GS1900Error::Parse { context: context.to_string(), line: line.text() }
+    }
+}
+
+/// A line of command output, or the fields of a table row
+trait OutputLine {
+    fn text(&self) -> String;
+}
+
+impl OutputLine for str {
+    fn text(&self) -> String {
+        self.trim().to_string()
+    }
+}
+
+impl OutputLine for std::vec::Vec<&str> {
+    fn text(&self) -> String {
+        self.join(" | ")
+    }
+}
+
+/// Turn a failed conversion into GS1900Error::Parse for the line it came from
+trait InLine<T> {
+    fn in_line<L: OutputLine + ?Sized>(self, line: &L) -> Result<T, GS1900Error>;
+}
+
+impl<T, E: std::fmt::Display> InLine<T> for Result<T, E> {
+    fn in_line<L: OutputLine + ?Sized>(self, line: &L) -> Result<T, GS1900Error> {
+        self.map_err(|e| GS1900Error::parse(e, line))
+    }
+}
+
+/// Check command output for CLI error messages, which are prefixed with '%'
+fn check_rejected(data: &str) -> Result<(), GS1900Error> {
+    for line in data.split('\n') {
+        let line = line.trim();
+        if line.starts_with('%') {
+            return Err(GS1900Error::CommandRejected { message: line.trim_start_matches('%').trim().to_string() });
+        }
+    }
+    Ok(())
+}
+
+/// Prompt at the end of the output received so far, ignoring terminal control sequences
+fn find_prompt(data: &str) -> Option<String> {
+    lazy_static! {
+        static ref RE: Regex = Regex::new(r"\x1b\[[0-9;?]*[A-Za-z]|\x00").unwrap();
+    }
+
+    let clean = RE.replace_all(data, "");
+    let last = clean.rsplit('\n').next().unwrap_or("").trim_start_matches('\r');
+    let trimmed = last.trim_end();
+    /* "Password:" is only expected after "enable" */
+    if trimmed.ends_with('#') || trimmed.ends_with('>') || trimmed.ends_with("Password:") {
+        return Some(last.to_string());
+    }
+    None
+}
+
+/// Check if a line is the command prompt (which is never indented)
+fn is_prompt(line: &str, prompt: &str) -> bool {
+    line.trim_start_matches('\r').trim_end() == prompt.trim()
+}
+
+/// What to do when no more command output arrives
+enum Idle {
+    /// the prompt ended the output
+    Done,
+    /// the pager waits for a key press after "--More--"
+    PressKey,
+}
+
+/// How long to wait for more command output. The output itself may contain
+/// a line looking like the prompt (e.g. a description equal to the hostname),
+/// so the prompt only ends the output if nothing follows within 200 ms.
+fn output_wait(data: &str, prompt: &str, timeout: std::time::Duration) -> std::time::Duration {
+    match output_idle(data, prompt) {
+        Some(_) => timeout.min(std::time::Duration::from_millis(200)),
+        None => timeout,
+    }
+}
+
+/// Check if the output received so far is complete or stopped at the pager,
+/// None if more output is expected
+fn output_idle(data: &str, prompt: &str) -> Option<Idle> {
+    let last = data.rsplit('\n').next().unwrap_or("");
+    if is_prompt(last, prompt) {
+        Some(Idle::Done)
+    } else if last.trim() == "--More--" {
+        Some(Idle::PressKey)
+    } else {
+        None
+    }
+}
+
+/// Blocking access to the interactive shell
+trait Shell {
+    /// Read available data, failing with TimedOut if nothing arrives within timeout
+    fn read_timeout(&mut self, buffer: &mut [u8], timeout: std::time::Duration) -> std::io::Result<usize>;
+    fn write_all(&mut self, data: &[u8]) -> std::io::Result<()>;
+    fn eof(&self) -> bool;
+}
+
+/// Shell on the SSH channel of a GS1900
+struct SshShell<'a> {
+    session: &'a ssh2::Session,
+    channel: &'a mut ssh2::Channel,
+}
+
+impl Shell for SshShell<'_> {
+    fn read_timeout(&mut self, buffer: &mut [u8], timeout: std::time::Duration) -> std::io::Result<usize> {
+        self.session.set_timeout(duration_ms(timeout));
+        self.channel.read(buffer)
+    }
+
+    fn write_all(&mut self, data: &[u8]) -> std::io::Result<()> {
+        self.channel.write_all(data)
+    }
+
+    fn eof(&self) -> bool {
+        self.channel.eof()
+    }
+}
+
+/// Read command output until the prompt appears, waiting up to timeout
+/// between chunks and passing every chunk to progress as it arrives
+fn read_output(shell: &mut dyn Shell, prompt: &str, command: &str, timeout: std::time::Duration, progress: &mut dyn FnMut(&str)) -> Result<String, GS1900Error> {
+    let mut data = String::new();
+    let mut pressed = false;
+    loop {
+        /* after a key press the pager line stays until the next page arrives */
+        let wait = if pressed { timeout } else { output_wait(&data, prompt, timeout) };
+        let mut buffer = [0; 100];
+        let len = match shell.read_timeout(&mut buffer, wait) {
+            Ok(x) => x,
+            Err(e) => match output_idle(&data, prompt) {
+                Some(Idle::PressKey) if pressed => {
+                    return Err(GS1900Error::Timeout {
+                        operation: format!("output of '{}' ({} bytes received)", command, data.len()),
+                    });
+                },
+                Some(Idle::Done) => return Ok(data),
+                Some(Idle::PressKey) => {
+                    /* pager key press, the only write not ending with a newline */
+                    shell.write_all(b" ")?;
+                    pressed = true;
+                    continue;
+                },
+                None if e.kind() == std::io::ErrorKind::TimedOut => {
+                    error!("timeout, unexpected data: {:?}", data.as_bytes());
+                    return Err(GS1900Error::Timeout {
+                        operation: format!("output of '{}' ({} bytes received)", command, data.len()),
+                    });
+                },
+                None => {
+                    error!("unexpected data: {:?}", data.as_bytes());
+                    return Err(truncated(command, &data));
+                },
+            },
+        };
+
+        /* connection closed before the prompt appeared */
+        if len == 0 && shell.eof() {
+            return Err(truncated(command, &data));
+        }
+
+        let append = String::from_utf8_lossy(&buffer[0..len]).to_string();
+        progress(&append);
+
+        data += &append;
+        pressed = false;
+    }
+}
+
+/// Read the response to a configuration command up to the next prompt. The
+/// prompt changes with the configuration mode ("switch(config-if)#") and the
+/// hostname, so any prompt is accepted. Returns the raw output and the prompt.
+fn read_mode_prompt(shell: &mut dyn Shell, command: &str, timeout: std::time::Duration) -> Result<(String, String), GS1900Error> {
+    let mut data = String::new();
+    loop {
+        /* an output line may end with '#' as well, see output_wait */
+        let prompt = find_prompt(&data);
+        let wait = match prompt {
+            Some(_) => timeout.min(std::time::Duration::from_millis(200)),
+            None => timeout,
+        };
+
+        let mut buffer = [0; 100];
+        let len = match shell.read_timeout(&mut buffer, wait) {
+            Ok(x) => x,
+            Err(e) => match prompt {
+                Some(prompt) => return Ok((data, prompt)),
+                None if e.kind() == std::io::ErrorKind::TimedOut => {
+                    return Err(GS1900Error::Timeout {
+                        operation: format!("output of '{}' ({} bytes received)", command, data.len()),
+                    });
+                },
+                None => return Err(e.into()),
+            },
+        };
+        if len == 0 && shell.eof() {
+            return Err(truncated(command, &data));
+        }
+        data += &String::from_utf8_lossy(&buffer[0..len]);
+    }
+}
+
+/// Remove the prompt and the pager artifacts from raw command output
+fn clean_output(data: &str, prompt: &str) -> String {
+    let tmp1 = data.replace(prompt, "");
+    let tmp2 = tmp1.replace("--More--\n", "");
+    let tmp3 = tmp2.replace("--More--\x08\n", "");
+    tmp3.replace("\x1b[A\x1b[2K", "")
+}
+
+/// Truncated error for the partial output of a command
+fn truncated(command: &str, data: &str) -> GS1900Error {
+    GS1900Error::Truncated {
+        command: command.to_string(),
+        got_rows: data.split('\n').filter(|x| !x.trim().is_empty()).count(),
+    }
+}
+
+/// Check that a table at the end of the output has no partial last row
+///
+/// data is the raw output including the trailing prompt line. The last row
+/// must have as many '|' columns as the rows and header above it, otherwise
+/// the output was cut off even though the prompt followed.
+fn table_complete(data: &str) -> bool {
+    let mut lines = data.split('\n')
+        .map(|x| x.trim_end_matches('\r'))
+        .rev()
+        .skip(1)
+        .skip_while(|x| x.trim().is_empty());
+
+    let last = match lines.next() {
+        Some(x) if x.contains('|') => x,
+        _ => return true,
+    };
+
+    let is_separator = |x: &str| x.contains('-') && x.trim().chars().all(|c| c == '-' || c == '+');
+    let expected = lines
+        .take_while(|x| x.contains('|') || is_separator(x))
+        .map(|x| x.matches('|').count())
+        .max()
+        .unwrap_or(0);
+
+    last.matches('|').count() >= expected
+}
+
+/// Check raw command output for CLI error messages and partial tables
+fn check_output(command: &str, data: &str) -> Result<(), GS1900Error> {
+    check_rejected(data)?;
+    if !table_complete(data) {
+        error!("incomplete table row: {:?}", data.as_bytes());
+        return Err(truncated(command, data));
+    }
+    Ok(())
+}
+
+/// Split '|' separated table output into trimmed fields, skipping
+/// header rows, unit rows and rows with less than min_cols columns
+fn parse_pipe_table<'a>(data: &'a str, min_cols: usize, header_marker: &'a str) -> impl Iterator<Item = std::vec::Vec<&'a str>> + 'a {
+    data.split('\n')
+        .map(|line| line.split('|').map(|x| x.trim()).collect::<std::vec::Vec<&str>>())
+        .filter(move |e| e.len() >= min_cols && !e[0].is_empty() && e[0] != header_marker)
+}
+
+/// Expand a list of numbers and ranges like "1-3,5" into [1, 2, 3, 5]
+fn expand_ranges(list: &str) -> std::io::Result<std::vec::Vec<u32>> {
+    let mut result = std::vec::Vec::new();
+    let invalid = || std::io::Error::new(std::io::ErrorKind::InvalidData, format!("Could not parse range {}", list));
+
+    for item in list.split(',').map(|x| x.trim()).filter(|x| !x.is_empty()) {
+        match item.find('-') {
+            Some(i) => {
+                let start: u32 = item[..i].trim().parse().map_err(|_| invalid())?;
+                let end: u32 = item[i+1..].trim().parse().map_err(|_| invalid())?;
+                if start > end {
+                    return Err(invalid());
+                }
+                result.extend(start..=end);
+            },
+            None => result.push(item.parse().map_err(|_| invalid())?),
+        }
+    }
+
+    Ok(result)
+}
+
+/// Per second rate of a counter sampled twice (handles counter wrap-around)
+fn counter_rate(old: u64, new: u64, elapsed: std::time::Duration) -> f32 {
+    let secs = elapsed.as_secs_f32();
+    if secs <= 0.0 {
+        return 0.0;
+    }
+    new.wrapping_sub(old) as f32 / secs
+}
+
 /// Access to GS1900 switch
 pub struct GS1900 {
     address: String,
-    username: String,
-    password: String,
+    config: ConnectionConfig,
     session: ssh2::Session,
     channel: ssh2::Channel,
     prompt: String,
+    last_output: String,
+    last_command: String,
+    features: std::collections::HashMap<Feature, bool>,
+    sfp_ports: Option<std::vec::Vec<u8>>,
+    counter_baseline: std::collections::HashMap<u8, InterfaceTrafficStatus>,
+}
+
+#[derive(Clone)]
+/// Connection parameters, which can be shared between switches
+pub struct ConnectionConfig {
+    /// SSH (and HTTP) user name
+    pub username: String,
+    /// SSH (and HTTP) password (None for SSH key authentication)
+    pub password: Option<String>,
+    /// SSH private key file and its passphrase, used instead of the password
+    pub private_key: Option<(std::path::PathBuf, Option<String>)>,
+    /// SSH port
+    pub ssh_port: u16,
+    /// timeout for establishing the TCP connection and the SSH login
+    pub connect_timeout: std::time::Duration,
+    /// maximum time to wait for more output of a command
+    pub command_timeout: std::time::Duration,
+    /// require the exact clear-screen sequence sent by the tested firmware versions before the prompt
+    pub strict: bool,
+    /// number of reconnects done by with_retry
+    pub retries: u32,
+    /// delay before the first reconnect (multiplied by the attempt number)
+    pub retry_backoff: std::time::Duration,
+    /// refuse every command changing the switch state (configuration, clearing counters, web control)
+    pub read_only: bool,
+    /// HTTP connect timeout
+    #[cfg(feature = "web")]
+    pub http_connect_timeout: std::time::Duration,
+    /// HTTP request timeout
+    #[cfg(feature = "web")]
+    pub http_timeout: std::time::Duration,
+}
+
+impl ConnectionConfig {
+    /// Connection parameters with default settings
+    pub fn new(username: &str, password: &str) -> ConnectionConfig {
+        ConnectionConfig {
+            username: username.to_string(),
+            password: Some(password.to_string()),
+            private_key: None,
+            ssh_port: 22,
+            connect_timeout: std::time::Duration::from_secs(10),
+            command_timeout: std::time::Duration::from_secs(1),
+            strict: false,
+            retries: 2,
+            retry_backoff: std::time::Duration::from_secs(1),
+            read_only: false,
+            #[cfg(feature = "web")]
+            http_connect_timeout: std::time::Duration::from_secs(5),
+            #[cfg(feature = "web")]
+            http_timeout: std::time::Duration::from_secs(10),
+        }
+    }
+
+    /// Connection parameters with default settings using SSH public key authentication
+    pub fn with_key(username: &str, private_key: &std::path::Path, passphrase: Option<&str>) -> ConnectionConfig {
+        let mut config = ConnectionConfig::new(username, "");
+        config.password = None;
+        config.private_key = Some((private_key.to_path_buf(), passphrase.map(|x| x.to_string())));
+        config
+    }
+}
+
+/* keep the password out of debug output */
+impl std::fmt::Debug for ConnectionConfig {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let mut d = f.debug_struct("ConnectionConfig");
+        d.field("username", &self.username)
+            .field("password", &self.password.as_ref().map(|_| "***"))
+            .field("private_key", &self.private_key.as_ref().map(|(path, _)| path))
+            .field("ssh_port", &self.ssh_port)
+            .field("connect_timeout", &self.connect_timeout)
+            .field("command_timeout", &self.command_timeout)
+            .field("strict", &self.strict)
+            .field("retries", &self.retries)
+            .field("retry_backoff", &self.retry_backoff)
+            .field("read_only", &self.read_only);
+        #[cfg(feature = "web")]
+        d.field("http_connect_timeout", &self.http_connect_timeout)
+            .field("http_timeout", &self.http_timeout);
+        d.finish()
+    }
 }
 
 #[derive(Debug)]
@@ -150,10 +800,10 @@ pub struct BasicInfo {
     pub ip_address: IPv4Address,
     /// System Subnet mask
     pub subnet_mask: IPv4Address,
-    /// Boot version
-    pub boot_version: String,
-    /// Firmware version
-    pub firmware_version: String,
+    /// Boot version (only raw is set if the format is unknown)
+    pub boot_version: FirmwareVersion,
+    /// Firmware version (only raw is set if the format is unknown)
+    pub firmware_version: FirmwareVersion,
     /// System object ID
     pub system_object_id: String,
     /// System uptime (in seconds)
@@ -170,8 +820,8 @@ impl Default for BasicInfo {
             mac_address: MacAddress::default(),
             ip_address: IPv4Address::default(),
             subnet_mask: IPv4Address::default(),
-            boot_version: "".to_string(),
-            firmware_version: "".to_string(),
+            boot_version: FirmwareVersion::default(),
+            firmware_version: FirmwareVersion::default(),
             system_object_id: "".to_string(),
             system_uptime: 0,
         }
@@ -206,7 +856,55 @@ pub struct LLDPNeighbor {
     pub ttl: u32,
 }
 
-#[derive(Debug)]
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+/// LLDP transmit/receive setting of a port
+pub enum LLDPAdminStatus {
+    TxRx,
+    TxOnly,
+    RxOnly,
+    Disabled,
+}
+
+impl std::str::FromStr for LLDPAdminStatus {
+    type Err = std::io::Error;
+
+    fn from_str (s: &str) -> Result<LLDPAdminStatus, std::io::Error> {
+        let s = s.to_uppercase();
+        let tx = s.contains("TX");
+        let rx = s.contains("RX");
+        match (tx, rx) {
+            (true, true) => Ok(LLDPAdminStatus::TxRx),
+            (true, false) => Ok(LLDPAdminStatus::TxOnly),
+            (false, true) => Ok(LLDPAdminStatus::RxOnly),
+            _ if s.starts_with("DISABLE") => Ok(LLDPAdminStatus::Disabled),
+            _ => Err(std::io::Error::new(std::io::ErrorKind::InvalidData, format!("Could not parse {}", s))),
+        }
+    }
+}
+
+#[derive(Debug, Copy, Clone)]
+/// LLDP settings of a port
+pub struct LLDPPortConfig {
+    /// port number
+    pub port: u8,
+    /// LLDP transmit/receive setting
+    pub admin_status: LLDPAdminStatus,
+}
+
+#[derive(Debug, Clone)]
+/// LLDP configuration
+pub struct LLDPConfig {
+    /// LLDP is enabled globally
+    pub enabled: bool,
+    /// interval between LLDP advertisements (in seconds)
+    pub tx_interval: u32,
+    /// TTL announced to neighbors (in seconds)
+    pub ttl: u32,
+    /// per port settings
+    pub ports: std::vec::Vec<LLDPPortConfig>,
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
 /// Type of Entry in MAC address table
 pub enum MacEntryType {
     Management,
@@ -227,11 +925,11 @@ impl std::str::FromStr for MacEntryType {
     }
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
 /// MAC address table entry
 pub struct MacEntry {
     /// VLAN ID
-    pub vlan_id: u8,
+    pub vlan_id: u16,
     /// MAC address
     pub mac_address: MacAddress,
     /// Type of entry (dynamic or static)
@@ -240,6 +938,15 @@ pub struct MacEntry {
     pub ports: String,
 }
 
+#[derive(Debug, Clone, Default)]
+/// Difference between two MAC address tables
+pub struct MacTableDelta {
+    /// entries which are new
+    pub added: std::vec::Vec<MacEntry>,
+    /// entries which disappeared
+    pub removed: std::vec::Vec<MacEntry>,
+}
+
 #[derive(Debug)]
 /// Status for SFP information
 pub enum SFPStatus {
@@ -292,6 +999,87 @@ pub struct FiberInfo {
     pub present: bool,
     /// Link detected?
     pub link: bool,
+    /// Temperature margin to the alarm threshold (in milli Celsius), if computed by the firmware
+    pub temperature_margin: Option<i32>,
+    /// Output power margin (in dB), if computed by the firmware
+    pub tx_power_margin_db: Option<f32>,
+    /// Input power margin (in dB), if computed by the firmware
+    pub rx_power_margin_db: Option<f32>,
+}
+
+impl FiberInfo {
+    /// Temperature in degrees Celsius
+    pub fn temperature_celsius(&self) -> f32 {
+        self.temperature as f32 / 1000.0
+    }
+
+    /// Voltage in Volts
+    pub fn voltage_volts(&self) -> f32 {
+        self.voltage as f32 / 1000.0
+    }
+
+    /// Current in mA
+    pub fn current_milliamps(&self) -> f32 {
+        self.current as f32 / 1000.0
+    }
+
+    /// Output power in dBm (negative infinity without any output power)
+    pub fn tx_power_dbm(&self) -> f32 {
+        uw_to_dbm(self.output_power)
+    }
+
+    /// Input power in dBm (negative infinity without any input power)
+    pub fn rx_power_dbm(&self) -> f32 {
+        uw_to_dbm(self.input_power)
+    }
+}
+
+/// Timestamp prefix of a system log line (empty if there is none)
+fn log_timestamp(line: &str) -> String {
+    lazy_static! {
+        static ref TIME: Regex = Regex::new(r"^\s*(\w{3}\s+\d+\s+(?:\d{4}\s+)?\d+:\d+:\d+)").unwrap();
+    }
+
+    TIME.captures(line).map(|c| c[1].to_string()).unwrap_or_default()
+}
+
+/// Reject commands containing line breaks or other control characters
+fn check_command(cmd: &str) -> Result<(), GS1900Error> {
+    if cmd.contains(|c: char| c.is_control()) {
+        return Err(std::io::Error::new(std::io::ErrorKind::InvalidInput, "Commands must not contain control characters").into());
+    }
+    Ok(())
+}
+
+/// Check if a command only displays information ("show", also abbreviated like "sh")
+fn is_show_command(cmd: &str) -> bool {
+    match cmd.split_whitespace().next() {
+        Some(word) => word.len() >= 2 && "show".starts_with(word),
+        None => false,
+    }
+}
+
+/// Quote a free text value for the CLI, values with quotes or control characters are rejected
+fn quote_arg(value: &str) -> Result<String, GS1900Error> {
+    if value.is_empty() || value.contains(|c: char| c.is_control() || c == '"') {
+        return Err(std::io::Error::new(std::io::ErrorKind::InvalidInput, format!("Invalid value {:?}", value)).into());
+    }
+
+    if value.contains(char::is_whitespace) {
+        Ok(format!("\"{}\"", value))
+    } else {
+        Ok(value.to_string())
+    }
+}
+
+/// Duration in milliseconds as expected by ssh2 timeouts
+fn duration_ms(d: std::time::Duration) -> u32 {
+    d.as_millis().min(u32::MAX as u128) as u32
+}
+
+/// Convert power in uW to dBm (0 dBm = 1 mW)
+fn uw_to_dbm(uw: i32) -> f32 {
+    10.0 * (uw as f32 / 1000.0).log10()
 }
 
 /// PoE classification (0-4)
@@ -402,7 +1190,33 @@ pub struct PoEDebug {
     pub reason: String,
 }
 
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+/// Kind of PoE event
+pub enum PoEEventKind {
+    /// powered device drew more than the allowed power
+    Overload,
+    /// short circuit detected
+    Short,
+    /// not enough power budget left for the powered device
+    PowerDenied,
+    /// powered device removed
+    Disconnected,
+    /// powered device detected and powered
+    Connected,
+}
+
 #[derive(Debug)]
+/// PoE event from the system log
+pub struct PoEEvent {
+    /// Interface number
+    pub port: u8,
+    /// timestamp as reported by the switch
+    pub timestamp: String,
+    /// event type
+    pub event: PoEEventKind,
+}
+
+#[derive(Debug, PartialEq)]
 /// PoE power allocation mode
 pub enum PoEMode {
     /// Allocate power based on device classification
@@ -423,7 +1237,7 @@ impl std::str::FromStr for PoEMode {
     }
 }
 
-#[derive(Debug)]
+#[derive(Debug, PartialEq)]
 /// PoE power-up sequence
 pub enum PoEPowerUpSequence {
     /// Enable PoE ports one after each other
@@ -444,7 +1258,7 @@ impl std::str::FromStr for PoEPowerUpSequence {
     }
 }
 
-#[derive(Debug)]
+#[derive(Debug, PartialEq)]
 /// PoE configuration
 pub struct PoEConfig {
     /// PoE Management Mode (classification vs consumption)
@@ -466,15 +1280,59 @@ impl Default for PoEConfig {
     }
 }
 
-#[derive(Debug)]
-/// PoE power-supply information
-pub struct PoESupply {
-    /// Power Supply unit (usually 0)
+#[derive(Debug, Clone, PartialEq, Eq)]
+/// PoE power-supply power state
+pub enum SupplyPower {
+    On,
+    Off,
+    /// unexpected value reported by the firmware
+    Other(String),
+}
+
+impl std::str::FromStr for SupplyPower {
+    type Err = std::io::Error;
+
+    fn from_str (s: &str) -> Result<SupplyPower, std::io::Error> {
+        match s {
+            "On" => Ok(SupplyPower::On),
+            "Off" => Ok(SupplyPower::Off),
+            _ => Ok(SupplyPower::Other(s.to_string())),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+/// PoE power-supply health
+pub enum SupplyStatus {
+    Ok,
+    Fault,
+    NotPresent,
+    /// unexpected value reported by the firmware
+    Other(String),
+}
+
+impl std::str::FromStr for SupplyStatus {
+    type Err = std::io::Error;
+
+    fn from_str (s: &str) -> Result<SupplyStatus, std::io::Error> {
+        match s {
+            "OK" => Ok(SupplyStatus::Ok),
+            "Fault" | "FAULT" | "Fail" => Ok(SupplyStatus::Fault),
+            "N/A" | "Not Present" | "NotPresent" => Ok(SupplyStatus::NotPresent),
+            _ => Ok(SupplyStatus::Other(s.to_string())),
+        }
+    }
+}
+
+#[derive(Debug)]
+/// PoE power-supply information
+pub struct PoESupply {
+    /// Power Supply unit (usually 0)
     pub unit: u8,
+    /// Power Supply power state
+    pub power: SupplyPower,
     /// Power Supply status
-    pub power: String,
-    /// Power Supply status
-    pub status: String,
+    pub status: SupplyStatus,
     /// Nominal Power of the power-supply in Watts
     pub nominal_power: u32,
     /// Allocated Power of the power-supply in Watts
@@ -500,6 +1358,8 @@ pub struct PoEPort {
     pub voltage: i32,
     /// current (mA)
     pub current: i32,
+    /// peak power since last reset (mW), if tracked by the firmware
+    pub peak_power: Option<i32>,
 }
 
 #[derive(Debug, Copy, Clone)]
@@ -647,6 +1507,33 @@ impl std::str::FromStr for MediaType {
     }
 }
 
+#[derive(Debug, Copy, Clone)]
+/// Port VLAN mode
+pub enum PortMode {
+    /// Untagged member of a single VLAN
+    Access,
+    /// Tagged member of multiple VLANs
+    Trunk,
+    /// Mix of tagged and untagged VLANs
+    Hybrid,
+}
+
+impl std::str::FromStr for PortMode {
+    type Err = std::io::Error;
+
+    fn from_str (s: &str) -> Result<PortMode, std::io::Error> {
+        match s {
+            "Access" => Ok(PortMode::Access),
+            "access" => Ok(PortMode::Access),
+            "Trunk" => Ok(PortMode::Trunk),
+            "trunk" => Ok(PortMode::Trunk),
+            "Hybrid" => Ok(PortMode::Hybrid),
+            "hybrid" => Ok(PortMode::Hybrid),
+            _ => Err(std::io::Error::new(std::io::ErrorKind::InvalidData, format!("Could not parse {}", s))),
+        }
+    }
+}
+
 #[derive(Debug)]
 /// Port status
 pub struct InterfaceStatus {
@@ -664,6 +1551,22 @@ pub struct InterfaceStatus {
     pub speed: PortSpeed,
     /// media type (copper, fiber)
     pub mediatype: MediaType,
+    /// VLAN mode (access, trunk, hybrid), None if the switchport output has no entry for the port
+    pub mode: Option<PortMode>,
+    /// port has an SFP cage (dedicated SFP or combo port), cable diagnostics are not available
+    /// while it uses the fiber side
+    pub is_uplink: bool,
+}
+
+#[derive(Debug)]
+/// Lightweight port inventory entry
+pub struct PortDescriptor {
+    /// port number
+    pub port: u8,
+    /// media type (copper, fiber)
+    pub media: MediaType,
+    /// port name
+    pub name: String,
 }
 
 #[derive(Debug, Copy, Clone)]
@@ -679,56 +1582,66 @@ pub struct InterfaceTrafficStatus {
     pub speed: PortSpeed,
     /// media type (Fiber or Copper)
     pub media_type: MediaType,
-    /// flow control
-    pub flow_control: bool,
+    /// flow control configured
+    pub flow_control_configured: bool,
+    /// flow control negotiated with the link partner
+    pub flow_control_active: bool,
     /// received packets
-    pub input_packets: u32,
+    pub input_packets: u64,
     /// received bytes
-    pub input_bytes: u32,
+    pub input_bytes: u64,
     /// received throttles
-    pub input_throttles: u32,
+    pub input_throttles: u64,
     /// received broadcasts
-    pub input_broadcasts: u32,
+    pub input_broadcasts: u64,
     /// received multicasts
-    pub input_multicasts: u32,
+    pub input_multicasts: u64,
     /// runts
-    pub input_runts: u32,
+    pub input_runts: u64,
     /// giants
-    pub input_giants: u32,
+    pub input_giants: u64,
     /// input errors
-    pub input_errors: u32,
+    pub input_errors: u64,
     /// input errors (CRC)
-    pub input_crc: u32,
+    pub input_crc: u64,
     /// input errors (frame)
-    pub input_frame: u32,
+    pub input_frame: u64,
     /// input errors (overrun)
-    pub input_overrun: u32,
+    pub input_overrun: u64,
     /// input errors (ignored)
-    pub input_ignored: u32,
+    pub input_ignored: u64,
     /// pause input
-    pub input_pause: u32,
+    pub input_pause: u64,
     /// input packets with dribble condition detected
-    pub input_dribble: u32,
+    pub input_dribble: u64,
     /// output packets
-    pub output_packets: u32,
+    pub output_packets: u64,
     /// output bytes
-    pub output_bytes: u32,
+    pub output_bytes: u64,
     /// output underrun
-    pub output_underrun: u32,
+    pub output_underrun: u64,
     /// output errors
-    pub output_errors: u32,
+    pub output_errors: u64,
     /// output collisions
-    pub output_collisions: u32,
+    pub output_collisions: u64,
     /// output interface resets
-    pub output_interface_resets: u32,
+    pub output_interface_resets: u64,
     /// babbles
-    pub output_babbles: u32,
+    pub output_babbles: u64,
     /// late collisions
-    pub output_late_collisions: u32,
+    pub output_late_collisions: u64,
     /// deferred
-    pub output_deferred: u32,
+    pub output_deferred: u64,
     /// paused
-    pub output_paused: u32,
+    pub output_paused: u64,
+    /// short-interval input rate in bits/sec (if reported by the firmware)
+    pub input_rate_bps: Option<u64>,
+    /// short-interval input rate in packets/sec (if reported by the firmware)
+    pub input_rate_pps: Option<u64>,
+    /// short-interval output rate in bits/sec (if reported by the firmware)
+    pub output_rate_bps: Option<u64>,
+    /// short-interval output rate in packets/sec (if reported by the firmware)
+    pub output_rate_pps: Option<u64>,
 }
 
 impl Default for InterfaceTrafficStatus {
@@ -740,7 +1653,8 @@ impl Default for InterfaceTrafficStatus {
             duplex: PortDuplex::Auto,
             speed: PortSpeed { auto: false, speed: 0 },
             media_type: MediaType::Copper,
-            flow_control: false,
+            flow_control_configured: false,
+            flow_control_active: false,
             input_packets: 0,
             input_bytes: 0,
             input_throttles: 0,
@@ -765,10 +1679,81 @@ impl Default for InterfaceTrafficStatus {
             output_late_collisions: 0,
             output_deferred: 0,
             output_paused: 0,
+            input_rate_bps: None,
+            input_rate_pps: None,
+            output_rate_bps: None,
+            output_rate_pps: None,
+        }
+    }
+}
+
+impl InterfaceTrafficStatus {
+    /// Counter difference to an older sample of the same port (handles counter wrap-around),
+    /// all other fields are taken from self
+    fn counter_delta(&self, old: &InterfaceTrafficStatus) -> InterfaceTrafficStatus {
+        InterfaceTrafficStatus {
+            input_packets: self.input_packets.wrapping_sub(old.input_packets),
+            input_bytes: self.input_bytes.wrapping_sub(old.input_bytes),
+            input_throttles: self.input_throttles.wrapping_sub(old.input_throttles),
+            input_broadcasts: self.input_broadcasts.wrapping_sub(old.input_broadcasts),
+            input_multicasts: self.input_multicasts.wrapping_sub(old.input_multicasts),
+            input_runts: self.input_runts.wrapping_sub(old.input_runts),
+            input_giants: self.input_giants.wrapping_sub(old.input_giants),
+            input_errors: self.input_errors.wrapping_sub(old.input_errors),
+            input_crc: self.input_crc.wrapping_sub(old.input_crc),
+            input_frame: self.input_frame.wrapping_sub(old.input_frame),
+            input_overrun: self.input_overrun.wrapping_sub(old.input_overrun),
+            input_ignored: self.input_ignored.wrapping_sub(old.input_ignored),
+            input_pause: self.input_pause.wrapping_sub(old.input_pause),
+            input_dribble: self.input_dribble.wrapping_sub(old.input_dribble),
+            output_packets: self.output_packets.wrapping_sub(old.output_packets),
+            output_bytes: self.output_bytes.wrapping_sub(old.output_bytes),
+            output_underrun: self.output_underrun.wrapping_sub(old.output_underrun),
+            output_errors: self.output_errors.wrapping_sub(old.output_errors),
+            output_collisions: self.output_collisions.wrapping_sub(old.output_collisions),
+            output_interface_resets: self.output_interface_resets.wrapping_sub(old.output_interface_resets),
+            output_babbles: self.output_babbles.wrapping_sub(old.output_babbles),
+            output_late_collisions: self.output_late_collisions.wrapping_sub(old.output_late_collisions),
+            output_deferred: self.output_deferred.wrapping_sub(old.output_deferred),
+            output_paused: self.output_paused.wrapping_sub(old.output_paused),
+            ..*self
         }
     }
 }
 
+#[derive(Debug, Copy, Clone)]
+/// Received broadcast and multicast frame rates of a port
+pub struct StormRates {
+    /// port number
+    pub port: u8,
+    /// received broadcast frames per second
+    pub bcast_pps: f32,
+    /// received multicast frames per second
+    pub mcast_pps: f32,
+}
+
+#[derive(Debug, Copy, Clone)]
+/// Recent link utilization of a port
+pub struct PortUtilization {
+    /// port number
+    pub port: u8,
+    /// receive utilization in percent of the line rate
+    pub rx_percent: f32,
+    /// transmit utilization in percent of the line rate
+    pub tx_percent: f32,
+}
+
+#[derive(Debug, Copy, Clone)]
+/// Egress queue counters
+pub struct QueueStat {
+    /// queue number
+    pub queue: u8,
+    /// enqueued packets
+    pub enqueued: u64,
+    /// dropped packets (tail drop)
+    pub dropped: u64,
+}
+
 #[derive(Debug)]
 /// VLAN type (static, dynamic)
 pub enum VLANType {
@@ -788,930 +1773,5089 @@ pub struct VLANInfo {
     pub ports_untagged: String,
     /// List of tagged ports in VLAN
     pub ports_tagged: String,
+    /// Untagged ports and LAGs in VLAN (None if ports_untagged could not be parsed)
+    pub members_untagged: Option<VLANMembers>,
+    /// Tagged ports and LAGs in VLAN (None if ports_tagged could not be parsed)
+    pub members_tagged: Option<VLANMembers>,
     /// VLAN type
     pub vlan_type: VLANType,
 }
 
-impl std::str::FromStr for VLANType {
-    type Err = std::io::Error;
+impl VLANInfo {
+    /// Untagged physical port numbers (empty if the member list could not be parsed)
+    pub fn ports_untagged_list(&self) -> std::vec::Vec<u8> {
+        self.members_untagged.as_ref().map(|x| x.ports.clone()).unwrap_or_default()
+    }
 
-    fn from_str (s: &str) -> Result<VLANType, std::io::Error> {
-        match s {
-            "Default" => Ok(VLANType::Default),
-            "Static" => Ok(VLANType::Static),
-            "Dynamic" => Ok(VLANType::Dynamic),
-            _ => Err(std::io::Error::new(std::io::ErrorKind::InvalidData, format!("Could not parse {}", s))),
-        }
+    /// Tagged physical port numbers (empty if the member list could not be parsed)
+    pub fn ports_tagged_list(&self) -> std::vec::Vec<u8> {
+        self.members_tagged.as_ref().map(|x| x.ports.clone()).unwrap_or_default()
     }
 }
 
-impl GS1900 {
-    /// Access the device
-    pub fn new(address: String, username: String, password: String) -> std::io::Result<GS1900> {
-        let addr = format!("{}:22", address);
-        let tcp = TcpStream::connect(addr)?;
-
-        let mut sess = Session::new()?;
-        sess.set_tcp_stream(tcp);
-        sess.handshake()?;
-        sess.userauth_password(username.as_str(), password.as_str())?;
-
-        let mut chan = sess.channel_session()?;
-        chan.shell()?;
-
-        let mut clearbuffer = [0; 7];
-        chan.read(&mut clearbuffer)?;
-
-        if clearbuffer != [27, 91, 72, 27, 91, 74, 0] {
-            return Err(std::io::Error::new(std::io::ErrorKind::Other, "Received invalid data"));
-        }
+/// Expand port lists like "gi1-gi4,gi7" or "1-4,7" into sorted port numbers.
+/// LAGs are skipped, see VLANMembers for the accepted syntax.
+pub fn parse_port_list(s: &str) -> std::io::Result<std::vec::Vec<u8>> {
+    Ok(s.parse::<VLANMembers>()?.ports)
+}
 
-        let mut prompt = [0; 32];
-        let len = chan.read(&mut prompt)?;
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+/// Sorted list of port numbers in the CLI range syntax ("1-4,7,9-12")
+pub struct PortRange {
+    /// port numbers (sorted, without duplicates)
+    pub ports: std::vec::Vec<u8>,
+}
 
-        Ok(GS1900 {address: address, username: username, password: password, session: sess, channel: chan, prompt: String::from_utf8_lossy(&prompt[0..len]).to_string()})
+impl PortRange {
+    pub fn new(ports: &[u8]) -> PortRange {
+        let mut ports = ports.to_vec();
+        ports.sort_unstable();
+        ports.dedup();
+        PortRange { ports }
     }
+}
 
-    fn fetch_data(&mut self) -> std::io::Result<String> {
-        self.session.set_timeout(1000);
+impl std::str::FromStr for PortRange {
+    type Err = std::io::Error;
 
-        let mut data = String::new();
-        loop {
-            let mut buffer = [0; 100];
-            let len = match self.channel.read(&mut buffer) {
-                Ok(x) => x,
-                Err(_e) => {
-                    let lines: Vec<&str> = data.split("\n").collect();
-                    let last = lines[lines.len()-1].trim();
-                    if last == self.prompt.trim() {
-                        return Ok(data);
-                    } else if last == "--More--" {
-                        self.channel.write(b" ")?;
-                        continue;
-                    } else {
-                        eprintln!("data: {:?}", data.as_bytes());
-                        return Err(std::io::Error::new(std::io::ErrorKind::Other, "Received invalid data"));
-                    }
-                },
-            };
+    fn from_str (s: &str) -> Result<PortRange, std::io::Error> {
+        let numbers = expand_ranges(s)?;
+        let ports = numbers.into_iter().map(std::convert::TryFrom::try_from).collect::<Result<std::vec::Vec<u8>, _>>()
+            .map_err(|_| std::io::Error::new(std::io::ErrorKind::InvalidData, format!("Could not parse {}", s)))?;
+        Ok(PortRange::new(&ports))
+    }
+}
 
-            let append = String::from_utf8_lossy(&buffer[0..len]).to_string();
+impl std::fmt::Display for PortRange {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let mut i = 0;
+        while i < self.ports.len() {
+            let start = self.ports[i];
+            while i + 1 < self.ports.len() && self.ports[i + 1] == self.ports[i] + 1 {
+                i += 1;
+            }
+            let end = self.ports[i];
 
-            data += &append;
+            if start != self.ports[0] {
+                write!(f, ",")?;
+            }
+            if start == end {
+                write!(f, "{}", start)?;
+            } else {
+                write!(f, "{}-{}", start, end)?;
+            }
+            i += 1;
         }
+        Ok(())
     }
+}
 
-    fn clean_data(&self, data: String) -> String {
-        let tmp1 = data.replace(self.prompt.as_str(), "");
-        let tmp2 = tmp1.replace("--More--\n", "");
-        let tmp3 = tmp2.replace("--More--\x08\n", "");
-        let tmp4 = tmp3.replace("\x1b[A\x1b[2K", "");
-        return tmp4;
-    }
-
-    pub fn basic_info(&mut self) -> std::io::Result<BasicInfo> {
-        self.channel.write(b"show info\n")?;
-        let mut result: BasicInfo = BasicInfo::default();
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+/// VLAN membership split into physical ports and link aggregation groups
+pub struct VLANMembers {
+    /// physical port numbers
+    pub ports: std::vec::Vec<u8>,
+    /// LAG (trunk) numbers
+    pub lags: std::vec::Vec<u8>,
+}
 
-        lazy_static! {
-            static ref RE1: Regex = Regex::new(r"(\d+) days, (\d+) hours, (\d+) mins, (\d+) secs").unwrap();
-        }
+impl std::str::FromStr for VLANMembers {
+    type Err = std::io::Error;
 
-        let raw = self.fetch_data()?;
-        let data = self.clean_data(raw);
+    /// Parse member lists like "1-4,8,Trunk1-2" or "gi1-gi4,gi7" (LAGs may also be named "LAG" or "Po")
+    fn from_str (s: &str) -> Result<VLANMembers, std::io::Error> {
+        let mut result = VLANMembers::default();
+        let invalid = || std::io::Error::new(std::io::ErrorKind::InvalidData, format!("Could not parse {}", s));
+        /* split "gi4" into "gi" and "4" */
+        let split_prefix = |x: &str| {
+            let i = x.find(|c: char| c.is_ascii_digit()).unwrap_or(x.len());
+            (x[..i].trim().to_lowercase(), x[i..].trim().to_string())
+        };
 
-        for line in data.split("\n") {
-            if line.trim() == self.prompt.trim() {
-                break;
+        for item in s.split(',').map(|x| x.trim()).filter(|x| !x.is_empty() && *x != "---" && *x != "-") {
+            let (first, last) = match item.find('-') {
+                Some(i) => (split_prefix(&item[..i]), split_prefix(&item[i+1..])),
+                None => (split_prefix(item), split_prefix(item)),
+            };
+            /* the end of a range may repeat the prefix ("gi1-gi4") or omit it ("Trunk1-2") */
+            if !last.0.is_empty() && last.0 != first.0 {
+                return Err(invalid());
             }
-
-            let kv: Vec<&str> = line.split(" : ").collect();
-            if kv.len() < 2 {
-                continue;
+            let start: u8 = first.1.parse().map_err(|_| invalid())?;
+            let end: u8 = last.1.parse().map_err(|_| invalid())?;
+            if start > end {
+                return Err(invalid());
             }
 
-            let key = kv[0].trim();
-            let val = kv[1].trim();
-
-            match key {
-                "System Name" => result.system_name = val.to_string(),
-                "System Location" => result.system_location = val.to_string(),
-                "System Contact" => result.system_contact = val.to_string(),
-                "MAC Address" => result.mac_address = val.to_string().parse::<MacAddress>()?,
-                "IP Address" => result.ip_address = val.to_string().parse::<IPv4Address>()?,
-                "Subnet Mask" => result.subnet_mask = val.to_string().parse::<IPv4Address>()?,
-                "Boot Version" => result.boot_version = val.to_string(),
-                "Firmware Version" => result.firmware_version = val.to_string(),
-                "System Object ID" => result.system_object_id = val.to_string(),
-                "System Up Time" => {
-                    for cap in RE1.captures_iter(line) {
-                        /* use unwrap, since regex caps are guaranteed to be numbers only */
-                        let days: u64 = cap[1].parse().unwrap();
-                        let hours: u64 = cap[2].parse().unwrap();
-                        let minutes: u64 = cap[3].parse().unwrap();
-                        let secs: u64 = cap[4].parse().unwrap();
-                        let timestamp: u64 = secs + minutes*60 + hours*3600 + days*86400;
-                        result.system_uptime = timestamp;
-                    }
-                },
-                _ => { return Err(std::io::Error::new(std::io::ErrorKind::Other, "Received invalid data")); },
+            match first.0.as_str() {
+                "" | "gi" | "gigabitethernet" | "port" => result.ports.extend(start..=end),
+                "trunk" | "lag" | "po" => result.lags.extend(start..=end),
+                _ => return Err(invalid()),
             }
         }
 
-        return Ok(result);
+        result.ports.sort_unstable();
+        result.ports.dedup();
+        result.lags.sort_unstable();
+        result.lags.dedup();
+        Ok(result)
     }
+}
 
-    pub fn lldp_info(&mut self) -> std::io::Result<std::vec::Vec::<LLDPNeighbor>> {
-        self.channel.write(b"show lldp neighbor\n")?;
+impl std::str::FromStr for VLANType {
+    type Err = std::io::Error;
 
-        let mut result = std::vec::Vec::<LLDPNeighbor>::new();
+    fn from_str (s: &str) -> Result<VLANType, std::io::Error> {
+        match s {
+            "Default" => Ok(VLANType::Default),
+            "Static" => Ok(VLANType::Static),
+            "Dynamic" => Ok(VLANType::Dynamic),
+            _ => Err(std::io::Error::new(std::io::ErrorKind::InvalidData, format!("Could not parse {}", s))),
+        }
+    }
+}
 
-        let raw = self.fetch_data()?;
-        let data = self.clean_data(raw);
+#[derive(Debug)]
+/// Power-on self-test result
+pub struct PostResult {
+    /// Name of the test
+    pub test: String,
+    /// Test passed?
+    pub passed: bool,
+    /// Additional information reported for the test
+    pub detail: String,
+}
 
-        for line in data.split("\n") {
-            if line.trim() == self.prompt.trim() {
-                break;
-            }
-            if line.trim() == "" {
-                continue;
-            }
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord)]
+/// Syslog severity (lower value is more severe)
+pub enum LogSeverity {
+    Emergency = 0,
+    Alert = 1,
+    Critical = 2,
+    Error = 3,
+    Warning = 4,
+    Notice = 5,
+    Informational = 6,
+    Debug = 7,
+}
 
-            let kv: Vec<&str> = line.split("|").collect();
-            if kv.len() < 6 {
-                continue;
-            }
+impl std::str::FromStr for LogSeverity {
+    type Err = std::io::Error;
 
-            if kv[0].trim() == "Port" {
-                continue;
-            }
+    fn from_str (s: &str) -> Result<LogSeverity, std::io::Error> {
+        match s {
+            "0" | "emerg" | "emergency" | "Emergency" => Ok(LogSeverity::Emergency),
+            "1" | "alert" | "Alert" => Ok(LogSeverity::Alert),
+            "2" | "crit" | "critical" | "Critical" => Ok(LogSeverity::Critical),
+            "3" | "err" | "error" | "Error" => Ok(LogSeverity::Error),
+            "4" | "warn" | "warning" | "Warning" => Ok(LogSeverity::Warning),
+            "5" | "notice" | "Notice" => Ok(LogSeverity::Notice),
+            "6" | "info" | "informational" | "Informational" => Ok(LogSeverity::Informational),
+            "7" | "debug" | "Debug" => Ok(LogSeverity::Debug),
+            _ => Err(std::io::Error::new(std::io::ErrorKind::InvalidData, format!("Could not parse {}", s))),
+        }
+    }
+}
 
-            let mut caps: LLDPCap = LLDPCap { bits: 0 };
-            let capsstr = kv[4].trim().to_string();
-            for cap in capsstr.split(", ") {
-                match cap {
-                    "Station Only" => caps.insert(LLDPCap::STATION),
-                    "Bridge" => caps.insert(LLDPCap::BRIDGE),
-                    "WLAN" => caps.insert(LLDPCap::WLAN),
-                    "Router" => caps.insert(LLDPCap::ROUTER),
-                    "Telephone" => caps.insert(LLDPCap::TELEPHONE),
-                    _ => {return Err(std::io::Error::new(std::io::ErrorKind::Other, format!("Received invalid LLDP capability: {}", cap)))},
-                }
-            }
+#[derive(Debug)]
+/// Syslog configuration
+pub struct SyslogConfig {
+    /// Logging into the RAM buffer is enabled
+    pub buffer_enabled: bool,
+    /// Lowest severity logged to the console
+    pub console_level: LogSeverity,
+    /// Remote syslog servers with their lowest logged severity
+    pub remote_servers: std::vec::Vec<(IPv4Address, LogSeverity)>,
+}
 
-            let neighbor = LLDPNeighbor {
-                port: kv[0].trim().parse().map_err(|_| std::io::Error::new(std::io::ErrorKind::InvalidData, "Failed to parse data"))?,
-                device_id: kv[1].trim().to_string(),
-                port_id: kv[2].trim().to_string(),
-                system_name: kv[3].trim().to_string(),
-                caps: caps,
-                ttl: kv[5].trim().parse().map_err(|_| std::io::Error::new(std::io::ErrorKind::InvalidData, "Failed to parse data"))?,
-            };
+#[derive(Debug)]
+/// Ping statistics
+pub struct PingResult {
+    /// Number of sent echo requests
+    pub transmitted: u8,
+    /// Number of received echo replies
+    pub received: u8,
+    /// Minimum round-trip time (0 if nothing was received)
+    pub min_ms: f32,
+    /// Average round-trip time (0 if nothing was received)
+    pub avg_ms: f32,
+    /// Maximum round-trip time (0 if nothing was received)
+    pub max_ms: f32,
+}
 
-            result.push(neighbor);
-        }
+#[derive(Debug)]
+/// Entry of the alarm/event table
+pub struct Alarm {
+    /// Alarm ID
+    pub id: u32,
+    /// Alarm severity
+    pub severity: LogSeverity,
+    /// Time of the event as reported by the switch
+    pub timestamp: String,
+    /// Event description
+    pub description: String,
+    /// Alarm is still ongoing (false for historical events)
+    pub active: bool,
+}
 
-        return Ok(result);
-    }
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+/// Reason for the last reboot
+pub enum RebootCause {
+    /// Power was lost (cold start)
+    PowerLoss,
+    /// Reset triggered by software (e.g. firmware upgrade)
+    SoftwareReset,
+    /// Hardware watchdog expired
+    Watchdog,
+    /// Reboot requested by a user
+    UserInitiated,
+    /// Firmware does not record the reason
+    Unknown,
+}
 
-    fn parse_fiber_entry(&self, entry: String) -> std::io::Result<(i32, String)> {
-        let splt: Vec<&str> = entry.split("  ").collect();
-        let result_int: i32;
-        let result_str: String;
-        if splt.len() >= 2 {
-            result_int = match splt[0].replace(".", "").parse() {
-                Ok(x) => x,
-                Err(_fail) => {
-                    return Err(std::io::Error::new(std::io::ErrorKind::Other, "Received invalid data"));
-                },
-            };
-            result_str = splt[1].replace("(", "").replace(")", "");
+impl std::str::FromStr for RebootCause {
+    type Err = std::io::Error;
+
+    fn from_str (s: &str) -> Result<RebootCause, std::io::Error> {
+        let lower = s.to_lowercase();
+        if lower.contains("power") || lower.contains("cold") {
+            Ok(RebootCause::PowerLoss)
+        } else if lower.contains("watchdog") {
+            Ok(RebootCause::Watchdog)
+        } else if lower.contains("user") || lower.contains("reload") || lower.contains("reboot") {
+            Ok(RebootCause::UserInitiated)
+        } else if lower.contains("software") || lower.contains("warm") || lower.contains("reset") {
+            Ok(RebootCause::SoftwareReset)
         } else {
-            result_int = 0;
-            result_str = entry;
+            Err(std::io::Error::new(std::io::ErrorKind::InvalidData, format!("Could not parse {}", s)))
         }
-        Ok((result_int*10, result_str))
     }
+}
 
-    pub fn fiber_info(&mut self) -> std::io::Result<()> {
-        self.channel.write(b"show fiber-transceiver interfaces all\n")?;
-
-        let raw = self.fetch_data()?;
-        let data = self.clean_data(raw);
+#[derive(Debug)]
+/// Information about the last reboot
+pub struct RebootInfo {
+    /// Reboot reason
+    pub cause: RebootCause,
+    /// Reason as reported by the switch
+    pub detail: String,
+}
 
-        for line in data.split("\n") {
-            let e: Vec<&str> = line.split("|").collect();
-            if e.len() < 8 {
-                continue;
-            }
-            if e[0].trim() == "Port" || e[0].trim() == "" {
-                continue;
-            }
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+/// ACL rule action
+pub enum ACLAction {
+    Permit,
+    Deny,
+}
 
-            let (temperature, temperature_status) = self.parse_fiber_entry(e[1].trim().to_string())?;
-            let (voltage, voltage_status) = self.parse_fiber_entry(e[2].trim().to_string())?;
-            let (current, current_status) = self.parse_fiber_entry(e[3].trim().to_string())?;
-            let (out_pwr, out_pwr_status) = self.parse_fiber_entry(e[4].trim().to_string())?;
-            let (in_pwr, in_pwr_status) = self.parse_fiber_entry(e[5].trim().to_string())?;
+impl std::str::FromStr for ACLAction {
+    type Err = std::io::Error;
 
-            let fi = FiberInfo {
-                port: match e[0].trim().parse() {
-                    Ok(x) => x,
-                    Err(_fail) => {
-                        return Err(std::io::Error::new(std::io::ErrorKind::Other, "Received invalid data"));
-                    },
-                },
-                temperature: temperature,
-                temperature_status: temperature_status.parse()?,
-                voltage: voltage,
-                voltage_status: voltage_status.parse()?,
-                current: current,
-                current_status: current_status.parse()?,
-                output_power: out_pwr,
-                output_power_status: out_pwr_status.parse()?,
-                input_power: in_pwr,
-                input_power_status: in_pwr_status.parse()?,
-                present: e[6].trim().to_string() == "Insert",
-                link: e[7].trim().to_string() == "Normal",
-            };
-            println!("{:?}", fi);
+    fn from_str (s: &str) -> Result<ACLAction, std::io::Error> {
+        match s {
+            "permit" | "Permit" => Ok(ACLAction::Permit),
+            "deny" | "Deny" => Ok(ACLAction::Deny),
+            _ => Err(std::io::Error::new(std::io::ErrorKind::InvalidData, format!("Could not parse {}", s))),
         }
-
-        return Ok(());
     }
+}
 
-    pub fn mac_table(&mut self) -> std::io::Result<std::vec::Vec::<MacEntry>> {
-        self.channel.write(b"show mac address-table\n")?;
-        let mut result = std::vec::Vec::<MacEntry>::new();
-
-        let raw = self.fetch_data()?;
-        let data = self.clean_data(raw);
-        let lines: Vec<&str> = data.split("\n").collect();
-
-        for line in lines {
-            let e: Vec<&str> = line.split("|").collect();
-            if e.len() < 4 {
-                continue;
-            }
-            if e[0].trim() == "VID" {
-                continue;
-            }
-
-            let mac = MacEntry {
-                vlan_id: match e[0].trim().parse() {
-                    Ok(x) => x,
-                    Err(_fail) => {
-                        return Err(std::io::Error::new(std::io::ErrorKind::Other, "Received invalid data"));
-                    },
-                },
-                mac_address: e[1].trim().to_string().parse()?,
-                entry_type: e[2].trim().to_string().parse()?,
-                ports: e[3].trim().to_string(),
-            };
+#[derive(Debug)]
+/// ACL match criteria (None matches any address)
+pub enum ACLMatch {
+    /// Match on source and destination MAC address
+    Mac { source: Option<MacAddress>, destination: Option<MacAddress> },
+    /// Match on source and destination IPv4 network (address, mask)
+    Ip { source: Option<(IPv4Address, IPv4Address)>, destination: Option<(IPv4Address, IPv4Address)> },
+    /// Criteria not modelled by this crate
+    Raw(String),
+}
 
-            result.push(mac);
+impl ACLMatch {
+    fn parse(acltype: &str, criteria: &str) -> ACLMatch {
+        let raw = ACLMatch::Raw(criteria.to_string());
+        let tokens: Vec<&str> = criteria.split_whitespace().collect();
+        if tokens.len() != 4 || tokens[0] != "src" || tokens[2] != "dst" {
+            return raw;
         }
 
-        return Ok(result);
+        match acltype {
+            "MAC" | "mac" => {
+                let parse_mac = |x: &str| if x == "any" { Ok(None) } else { x.parse::<MacAddress>().map(Some) };
+                match (parse_mac(tokens[1]), parse_mac(tokens[3])) {
+                    (Ok(source), Ok(destination)) => ACLMatch::Mac { source, destination },
+                    _ => raw,
+                }
+            },
+            "IP" | "ip" => {
+                let parse_net = |x: &str| -> std::io::Result<Option<(IPv4Address, IPv4Address)>> {
+                    if x == "any" {
+                        return Ok(None);
+                    }
+                    let net: Vec<&str> = x.split('/').collect();
+                    if net.len() != 2 {
+                        return Err(std::io::Error::new(std::io::ErrorKind::InvalidData, "Failed to parse data"));
+                    }
+                    Ok(Some((net[0].parse()?, net[1].parse()?)))
+                };
+                match (parse_net(tokens[1]), parse_net(tokens[3])) {
+                    (Ok(source), Ok(destination)) => ACLMatch::Ip { source, destination },
+                    _ => raw,
+                }
+            },
+            _ => raw,
+        }
     }
+}
 
-    pub fn mac_table_port(&mut self, port: u8) -> std::io::Result<std::vec::Vec::<MacEntry>> {
-        self.channel.write(b"show mac address-table interfaces ")?;
-        self.channel.write(format!("{}", port).as_bytes())?;
-        self.channel.write(b"\n")?;
-        let mut result = std::vec::Vec::<MacEntry>::new();
+#[derive(Debug)]
+/// Access control list rule
+pub struct ACLRule {
+    /// Rule ID (sequence number)
+    pub id: u32,
+    /// Action for matching frames
+    pub action: ACLAction,
+    /// Match criteria
+    pub match_criteria: ACLMatch,
+    /// Interfaces the rule is bound to
+    pub bound_ports: String,
+}
 
-        let raw = self.fetch_data()?;
-        let data = self.clean_data(raw);
-        let lines: Vec<&str> = data.split("\n").collect();
+#[derive(Debug)]
+/// Global loop/topology protection settings (None if unsupported by the firmware)
+pub struct ProtectionConfig {
+    /// STP BPDU guard
+    pub bpdu_guard: Option<bool>,
+    /// STP root guard
+    pub root_guard: Option<bool>,
+    /// Loop guard
+    pub loop_guard: Option<bool>,
+    /// DHCP snooping
+    pub dhcp_snooping: Option<bool>,
+}
 
-        for line in lines {
-            let e: Vec<&str> = line.split("|").collect();
-            if e.len() < 4 {
-                continue;
-            }
-            if e[0].trim() == "VID" {
-                continue;
-            }
+#[derive(Debug, Clone, PartialEq, Eq)]
+/// VLANs allowed on a trunk port
+pub enum AllowedVLANs {
+    /// every VLAN, including VLANs not created yet
+    All,
+    /// explicit list of VLAN IDs
+    List(std::vec::Vec<u32>),
+}
 
-            let mac = MacEntry {
-                vlan_id: match e[0].trim().parse() {
-                    Ok(x) => x,
-                    Err(_fail) => {
-                        return Err(std::io::Error::new(std::io::ErrorKind::Other, "Received invalid data"));
-                    },
-                },
-                mac_address: e[1].trim().to_string().parse()?,
-                entry_type: e[2].trim().to_string().parse()?,
-                ports: e[3].trim().to_string(),
-            };
+impl std::str::FromStr for AllowedVLANs {
+    type Err = std::io::Error;
 
-            result.push(mac);
+    fn from_str (s: &str) -> Result<AllowedVLANs, std::io::Error> {
+        match s {
+            "all" | "ALL" | "All" | "1-4094" => Ok(AllowedVLANs::All),
+            _ => Ok(AllowedVLANs::List(expand_ranges(s)?)),
         }
-
-        return Ok(result);
     }
+}
 
+/// Switchport settings of a single port
+struct Switchport {
+    /// None for port modes not known to this library
+    mode: Option<PortMode>,
+    pvid: u16,
+    allowed_vlans: Option<AllowedVLANs>,
+}
 
-    pub fn lookup_mac_address(&mut self, address: MacAddress) -> std::io::Result<std::option::Option<MacEntry>> {
-        self.channel.write(b"show mac address-table ")?;
-        self.channel.write(format!("{}", address).as_bytes())?;
-        self.channel.write(b"\n")?;
-
-        let raw = self.fetch_data()?;
-        let data = self.clean_data(raw);
-        let lines: Vec<&str> = data.split("\n").collect();
+#[derive(Debug, Copy, Clone)]
+/// Default 802.1p priority of a port
+pub struct PortPriority {
+    /// port number
+    pub port: u8,
+    /// CoS value assigned to untagged ingress frames
+    pub default_cos: u8,
+}
 
-        for line in lines {
-            let e: Vec<&str> = line.split("|").collect();
-            if e.len() < 4 {
-                continue;
+#[derive(Debug, Copy, Clone)]
+/// Spanning tree settings of a port
+pub struct STPPortConfig {
+    /// port number
+    pub port: u8,
+    /// path cost
+    pub cost: u32,
+    /// port priority
+    pub priority: u8,
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+/// Spanning tree protocol version
+pub enum STPMode {
+    /// spanning tree is switched off
+    Disabled,
+    /// IEEE 802.1D
+    STP,
+    /// IEEE 802.1w
+    RSTP,
+    /// IEEE 802.1s
+    MSTP,
+}
+
+impl std::str::FromStr for STPMode {
+    type Err = std::io::Error;
+
+    fn from_str (s: &str) -> Result<STPMode, std::io::Error> {
+        match s.to_lowercase().as_str() {
+            "disabled" => Ok(STPMode::Disabled),
+            "stp" => Ok(STPMode::STP),
+            "rstp" => Ok(STPMode::RSTP),
+            "mstp" => Ok(STPMode::MSTP),
+            _ => Err(std::io::Error::new(std::io::ErrorKind::InvalidData, format!("Could not parse {}", s))),
+        }
+    }
+}
+
+impl std::fmt::Display for STPMode {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            STPMode::Disabled => write!(f, "disabled"),
+            STPMode::STP => write!(f, "stp"),
+            STPMode::RSTP => write!(f, "rstp"),
+            STPMode::MSTP => write!(f, "mstp"),
+        }
+    }
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+/// Management service
+pub enum MgmtService {
+    SSH,
+    HTTP,
+    HTTPS,
+    SNMP,
+    Telnet,
+    /// every management service
+    All,
+}
+
+impl std::str::FromStr for MgmtService {
+    type Err = std::io::Error;
+
+    fn from_str (s: &str) -> Result<MgmtService, std::io::Error> {
+        match s.to_lowercase().as_str() {
+            "ssh" => Ok(MgmtService::SSH),
+            "http" => Ok(MgmtService::HTTP),
+            "https" => Ok(MgmtService::HTTPS),
+            "snmp" => Ok(MgmtService::SNMP),
+            "telnet" => Ok(MgmtService::Telnet),
+            "all" | "any" => Ok(MgmtService::All),
+            _ => Err(std::io::Error::new(std::io::ErrorKind::InvalidData, format!("Could not parse {}", s))),
+        }
+    }
+}
+
+#[derive(Debug)]
+/// Host/subnet allowed to access a management service
+pub struct ManagementACL {
+    /// management service
+    pub service: MgmtService,
+    /// allowed subnet
+    pub subnet: IPv4Address,
+    /// subnet mask
+    pub mask: IPv4Address,
+}
+
+#[derive(Debug, Copy, Clone, Default)]
+/// Optical link counters of a fiber port
+pub struct FiberCounters {
+    /// port number
+    pub port: u8,
+    /// receive errors
+    pub rx_errors: u32,
+    /// transmit errors
+    pub tx_errors: u32,
+    /// link drops
+    pub link_drops: u32,
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+/// GVRP registration mode of a port
+pub enum GVRPRegistration {
+    /// dynamic registration and deregistration
+    Normal,
+    /// no deregistration of dynamic VLANs
+    Fixed,
+    /// no dynamic VLAN registration
+    Forbidden,
+}
+
+impl std::str::FromStr for GVRPRegistration {
+    type Err = std::io::Error;
+
+    fn from_str (s: &str) -> Result<GVRPRegistration, std::io::Error> {
+        match s {
+            "Normal" | "normal" => Ok(GVRPRegistration::Normal),
+            "Fixed" | "fixed" => Ok(GVRPRegistration::Fixed),
+            "Forbidden" | "forbidden" => Ok(GVRPRegistration::Forbidden),
+            _ => Err(std::io::Error::new(std::io::ErrorKind::InvalidData, format!("Could not parse {}", s))),
+        }
+    }
+}
+
+#[derive(Debug)]
+/// GVRP configuration
+pub struct GVRPConfig {
+    /// GVRP enabled globally
+    pub enabled: bool,
+    /// registration mode of every port (empty if GVRP is disabled)
+    pub ports: std::collections::HashMap<u8, GVRPRegistration>,
+}
+
+#[derive(Debug)]
+/// Link state of a port combined with its LLDP neighbor
+pub struct PortTopology {
+    /// port number
+    pub port: u8,
+    /// link is up
+    pub connected: bool,
+    /// speed configuration
+    pub speed: PortSpeed,
+    /// LLDP neighbor (None for end hosts and unconnected ports)
+    pub neighbor: Option<LLDPNeighbor>,
+}
+
+#[derive(Debug, Copy, Clone)]
+/// DHCP snooping settings of a port
+pub struct DHCPSnoopPort {
+    /// port number
+    pub port: u8,
+    /// DHCP server messages are accepted on this port
+    pub trusted: bool,
+    /// rate limit in packets per second (None if unlimited)
+    pub rate_limit: Option<u32>,
+}
+
+#[derive(Debug, Copy, Clone)]
+/// Packets dropped on a port by a security feature
+pub struct PortDrops {
+    /// port number
+    pub port: u8,
+    /// dropped packets
+    pub dropped: u64,
+}
+
+#[derive(Debug, Clone)]
+/// DHCP snooping and dynamic ARP inspection drop counters (empty if the feature is disabled)
+pub struct SecurityViolations {
+    /// DHCP packets dropped by DHCP snooping
+    pub dhcp_snooping: std::vec::Vec<PortDrops>,
+    /// ARP packets dropped by dynamic ARP inspection
+    pub arp_inspection: std::vec::Vec<PortDrops>,
+}
+
+#[derive(Debug, Clone)]
+/// Recurring daylight saving time switch date
+pub struct DSTRecurrence {
+    /// week of the month (1-5, 5 meaning the last week)
+    pub week: u8,
+    /// day of the week (e.g. "Sun")
+    pub day: String,
+    /// month (e.g. "Mar")
+    pub month: String,
+    /// local time of the switch (hours, minutes)
+    pub time: (u8, u8),
+}
+
+#[derive(Debug, Clone)]
+/// Recurring daylight saving time rule
+pub struct DSTRule {
+    /// start of daylight saving time
+    pub start: DSTRecurrence,
+    /// end of daylight saving time
+    pub end: DSTRecurrence,
+    /// offset added during daylight saving time (in minutes)
+    pub offset_minutes: i16,
+}
+
+#[derive(Debug, Clone)]
+/// Time zone configuration
+pub struct TimezoneConfig {
+    /// time zone acronym
+    pub name: String,
+    /// offset to UTC (in minutes)
+    pub offset_minutes: i16,
+    /// recurring daylight saving time rule (None if DST is not configured or uses fixed dates)
+    pub dst: Option<DSTRule>,
+}
+
+impl DSTRecurrence {
+    fn new(week: u8, day: &str, month: &str, time: (u8, u8)) -> DSTRecurrence {
+        DSTRecurrence { week, day: day.to_string(), month: month.to_string(), time }
+    }
+
+    /// Parse "<week> <day> <month> <hh:mm>"
+    fn parse(tokens: &[&str]) -> std::io::Result<DSTRecurrence> {
+        let invalid = || std::io::Error::new(std::io::ErrorKind::InvalidData, "Failed to parse data");
+        if tokens.len() < 4 {
+            return Err(invalid());
+        }
+
+        let week = match tokens[0] {
+            "first" => 1,
+            "last" => 5,
+            x => x.parse().map_err(|_| invalid())?,
+        };
+        let time: Vec<&str> = tokens[3].split(':').collect();
+        if time.len() != 2 {
+            return Err(invalid());
+        }
+        let time = (time[0].parse().map_err(|_| invalid())?, time[1].parse().map_err(|_| invalid())?);
+
+        Ok(DSTRecurrence::new(week, tokens[1], tokens[2], time))
+    }
+}
+
+#[derive(Debug)]
+/// Management service states (None if unsupported by the firmware)
+pub struct ServiceStatus {
+    /// SSH server
+    pub ssh: Option<bool>,
+    /// Telnet server
+    pub telnet: Option<bool>,
+    /// HTTP server
+    pub http: Option<bool>,
+    /// HTTPS server
+    pub https: Option<bool>,
+    /// SNMP agent
+    pub snmp: Option<bool>,
+}
+
+#[derive(Debug, Copy, Clone)]
+/// Spanning tree BPDU counters of a port
+pub struct STPPortStats {
+    /// port number
+    pub port: u8,
+    /// transmitted BPDUs
+    pub bpdu_tx: u32,
+    /// received BPDUs
+    pub bpdu_rx: u32,
+    /// transmitted topology change notifications
+    pub tcn_tx: u32,
+    /// received topology change notifications
+    pub tcn_rx: u32,
+}
+
+#[derive(Debug)]
+/// Email alert configuration
+pub struct EmailAlertConfig {
+    /// email alerts are enabled
+    pub enabled: bool,
+    /// SMTP server
+    pub server: Option<String>,
+    /// sender address
+    pub sender: Option<String>,
+    /// recipient addresses
+    pub recipients: std::vec::Vec<String>,
+    /// severities triggering an email
+    pub severities: std::vec::Vec<LogSeverity>,
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+/// Auto-negotiation link capability
+pub enum LinkSpeed {
+    Half10,
+    Full10,
+    Half100,
+    Full100,
+    Half1000,
+    Full1000,
+}
+
+impl std::str::FromStr for LinkSpeed {
+    type Err = std::io::Error;
+
+    fn from_str (s: &str) -> Result<LinkSpeed, std::io::Error> {
+        match s.to_lowercase().replace(['-', ' ', '_'], "").as_str() {
+            "10mhalf" | "10half" | "10hd" => Ok(LinkSpeed::Half10),
+            "10mfull" | "10full" | "10fd" => Ok(LinkSpeed::Full10),
+            "100mhalf" | "100half" | "100hd" => Ok(LinkSpeed::Half100),
+            "100mfull" | "100full" | "100fd" => Ok(LinkSpeed::Full100),
+            "1000mhalf" | "1000half" | "1000hd" | "1ghalf" => Ok(LinkSpeed::Half1000),
+            "1000mfull" | "1000full" | "1000fd" | "1gfull" => Ok(LinkSpeed::Full1000),
+            _ => Err(std::io::Error::new(std::io::ErrorKind::InvalidData, format!("Could not parse {}", s))),
+        }
+    }
+}
+
+#[derive(Debug)]
+/// Auto-negotiation capabilities of a port
+pub struct AutonegStatus {
+    /// port number
+    pub port: u8,
+    /// capabilities advertised by the switch
+    pub advertised: std::vec::Vec<LinkSpeed>,
+    /// capabilities advertised by the link partner (empty without link partner)
+    pub partner: std::vec::Vec<LinkSpeed>,
+}
+
+#[derive(Debug)]
+/// IP interface of a VLAN
+pub struct IPInterface {
+    /// VLAN ID
+    pub vlan: u32,
+    /// IP address
+    pub ip: IPv4Address,
+    /// subnet mask
+    pub mask: IPv4Address,
+    /// interface is administratively up (None if not reported)
+    pub admin_up: Option<bool>,
+    /// interface link is up (None if not reported)
+    pub link_up: Option<bool>,
+}
+
+#[derive(Debug)]
+/// Static IPv4 route
+pub struct StaticRoute {
+    /// destination network (0.0.0.0 for the default route)
+    pub destination: IPv4Address,
+    /// destination prefix length (0 for the default route)
+    pub prefix_len: u8,
+    /// next hop
+    pub gateway: IPv4Address,
+    /// route metric, if reported by the firmware
+    pub metric: Option<u32>,
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+/// Front panel port LED mode
+pub enum LEDMode {
+    /// LEDs show link and activity
+    Link,
+    /// LEDs show PoE status
+    PoE,
+}
+
+impl std::str::FromStr for LEDMode {
+    type Err = std::io::Error;
+
+    fn from_str (s: &str) -> Result<LEDMode, std::io::Error> {
+        match s {
+            "Link" | "link" | "Link/Activity" | "link/act" => Ok(LEDMode::Link),
+            "PoE" | "poe" | "POE" => Ok(LEDMode::PoE),
+            _ => Err(std::io::Error::new(std::io::ErrorKind::InvalidData, format!("Could not parse {}", s))),
+        }
+    }
+}
+
+impl std::fmt::Display for LEDMode {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            LEDMode::Link => write!(f, "link"),
+            LEDMode::PoE => write!(f, "poe"),
+        }
+    }
+}
+
+#[derive(Debug, Copy, Clone)]
+/// Configured maximum frame size correlated with the size error counters of a port
+pub struct JumboDiagnosis {
+    /// port number
+    pub port: u8,
+    /// maximum frame size accepted by the switch (in bytes)
+    pub mtu: u32,
+    /// frames exceeding the maximum frame size (see jumbo_diagnosis)
+    pub input_giants: u64,
+    /// frames below the minimum frame size (see jumbo_diagnosis)
+    pub input_runts: u64,
+    /// giants make up at least 0.1% of the received frames, so the peer
+    /// probably sends larger frames than configured
+    pub likely_mtu_mismatch: bool,
+}
+
+/// Port mirroring sessions supported by the GS1900 series. Depending on the
+/// firmware version "show port-monitor" lists every session slot or only the
+/// configured ones, so the number of listed sessions is not the limit.
+const MIRROR_SESSIONS: u8 = 1;
+
+#[derive(Debug, Copy, Clone)]
+/// Port mirroring session usage
+pub struct MirrorCapacity {
+    /// number of mirror sessions supported by the switch
+    pub max_sessions: u8,
+    /// number of enabled mirror sessions
+    pub in_use: u8,
+}
+
+#[derive(Debug)]
+/// Over-temperature event from the system log
+pub struct ThermalEvent {
+    /// timestamp as reported by the switch
+    pub timestamp: String,
+    /// temperature sensor (empty if not reported)
+    pub sensor: String,
+    /// temperature in degrees Celsius, if reported
+    pub temperature: Option<f32>,
+    /// complete log message
+    pub message: String,
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+/// Optional capability of a switch model or firmware
+pub enum Feature {
+    /// Power over Ethernet
+    PoE,
+    /// fiber (SFP) ports
+    SFP,
+    /// static IP routing
+    Routing,
+    /// loop guard (loopback detection)
+    LoopGuard,
+    /// switchable port LED mode
+    LEDMode,
+    /// DHCP snooping
+    DHCPSnooping,
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+/// Action taken when a port exceeds the link flap threshold
+pub enum FlapAction {
+    /// disable the port until it is recovered
+    Shutdown,
+    /// only log the event
+    Log,
+}
+
+impl std::str::FromStr for FlapAction {
+    type Err = std::io::Error;
+
+    fn from_str (s: &str) -> Result<FlapAction, std::io::Error> {
+        match s {
+            "Shutdown" | "shutdown" => Ok(FlapAction::Shutdown),
+            "Log" | "log" => Ok(FlapAction::Log),
+            _ => Err(std::io::Error::new(std::io::ErrorKind::InvalidData, format!("Could not parse {}", s))),
+        }
+    }
+}
+
+impl std::fmt::Display for FlapAction {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            FlapAction::Shutdown => write!(f, "shutdown"),
+            FlapAction::Log => write!(f, "log"),
+        }
+    }
+}
+
+#[derive(Debug, Copy, Clone)]
+/// Link flap dampening settings of a port
+pub struct FlapDampening {
+    /// port number
+    pub port: u8,
+    /// link flap detection is enabled
+    pub enabled: bool,
+    /// number of link changes triggering the action
+    pub threshold: u32,
+    /// observation window in seconds
+    pub window_seconds: u32,
+    /// action taken when the threshold is exceeded
+    pub action: FlapAction,
+}
+
+#[derive(Debug)]
+/// DNS client configuration
+pub struct DNSConfig {
+    /// Configured name servers (empty if none are configured)
+    pub servers: std::vec::Vec<IPv4Address>,
+    /// Default domain name
+    pub domain: Option<String>,
+}
+
+/// Parse the output of "show info"
+fn parse_basic_info(data: &str) -> Result<BasicInfo, GS1900Error> {
+    let mut result: BasicInfo = BasicInfo::default();
+
+    lazy_static! {
+        static ref RE1: Regex = Regex::new(r"(\d+) days, (\d+) hours, (\d+) mins, (\d+) secs").unwrap();
+    }
+
+    for line in data.split("\n") {
+        let kv: Vec<&str> = line.split(" : ").collect();
+        if kv.len() < 2 {
+            continue;
+        }
+
+        let key = kv[0].trim();
+        let val = kv[1].trim();
+
+        match key {
+            "System Name" => result.system_name = val.to_string(),
+            "System Location" => result.system_location = val.to_string(),
+            "System Contact" => result.system_contact = val.to_string(),
+            "MAC Address" => result.mac_address = val.to_string().parse::<MacAddress>().in_line(line)?,
+            "IP Address" => result.ip_address = val.to_string().parse::<IPv4Address>().in_line(line)?,
+            "Subnet Mask" => result.subnet_mask = val.to_string().parse::<IPv4Address>().in_line(line)?,
+            "Boot Version" => result.boot_version = val.parse().unwrap_or_else(|_| FirmwareVersion { raw: val.to_string(), ..Default::default() }),
+            "Firmware Version" => result.firmware_version = val.parse().unwrap_or_else(|_| FirmwareVersion { raw: val.to_string(), ..Default::default() }),
+            "System Object ID" => result.system_object_id = val.to_string(),
+            "System Up Time" => {
+                for cap in RE1.captures_iter(line) {
+                    /* use unwrap, since regex caps are guaranteed to be numbers only */
+                    let days: u64 = cap[1].parse().unwrap();
+                    let hours: u64 = cap[2].parse().unwrap();
+                    let minutes: u64 = cap[3].parse().unwrap();
+                    let secs: u64 = cap[4].parse().unwrap();
+                    let timestamp: u64 = secs + minutes*60 + hours*3600 + days*86400;
+                    result.system_uptime = timestamp;
+                }
+            },
+            _ => return Err(GS1900Error::parse("Unknown system information", line)),
+        }
+    }
+
+    return Ok(result);
+}
+
+/// Parse the output of "show lldp neighbor"
+fn parse_lldp_neighbors(data: &str) -> Result<std::vec::Vec::<LLDPNeighbor>, GS1900Error> {
+    let mut result = std::vec::Vec::<LLDPNeighbor>::new();
+
+    for kv in parse_pipe_table(data, 6, "Port") {
+        let mut caps: LLDPCap = LLDPCap { bits: 0 };
+        for cap in kv[4].split(", ") {
+            match cap {
+                "Station Only" => caps.insert(LLDPCap::STATION),
+                "Bridge" => caps.insert(LLDPCap::BRIDGE),
+                "WLAN" => caps.insert(LLDPCap::WLAN),
+                "Router" => caps.insert(LLDPCap::ROUTER),
+                "Telephone" => caps.insert(LLDPCap::TELEPHONE),
+                _ => return Err(GS1900Error::parse(format!("Invalid LLDP capability '{}'", cap), &kv)),
+            }
+        }
+
+        let neighbor = LLDPNeighbor {
+            port: kv[0].parse().in_line(&kv)?,
+            device_id: kv[1].to_string(),
+            port_id: kv[2].to_string(),
+            system_name: kv[3].to_string(),
+            caps: caps,
+            ttl: kv[5].parse().in_line(&kv)?,
+        };
+
+        result.push(neighbor);
+    }
+
+    return Ok(result);
+}
+
+/// Split a fiber diagnostic value like "35.50  (Normal)" into value (x1000) and status
+fn parse_fiber_entry(entry: String) -> Result<(i32, String), GS1900Error> {
+    let splt: Vec<&str> = entry.split("  ").collect();
+    let result_int: i32;
+    let result_str: String;
+    if splt.len() >= 2 {
+        result_int = splt[0].replace(".", "").parse().in_line(entry.as_str())?;
+        result_str = splt[1].replace("(", "").replace(")", "");
+    } else {
+        result_int = 0;
+        result_str = entry;
+    }
+    Ok((result_int*10, result_str))
+}
+
+/// Parse an optional link budget margin column
+fn parse_fiber_margin(entry: Option<&&str>) -> Result<Option<f32>, GS1900Error> {
+    let val = match entry {
+        Some(x) => x.trim(),
+        None => return Ok(None),
+    };
+    if val.is_empty() || val == "N/A" || val == "-" {
+        return Ok(None);
+    }
+    let margin: f32 = val.parse().in_line(val)?;
+    Ok(Some(margin))
+}
+
+/// Parse the output of "show fiber-transceiver interfaces all"
+fn parse_fiber_info(data: &str) -> Result<std::vec::Vec::<FiberInfo>, GS1900Error> {
+    let mut result = std::vec::Vec::<FiberInfo>::new();
+
+    for e in parse_pipe_table(data, 8, "Port") {
+        let (temperature, temperature_status) = parse_fiber_entry(e[1].to_string())?;
+        let (voltage, voltage_status) = parse_fiber_entry(e[2].to_string())?;
+        let (current, current_status) = parse_fiber_entry(e[3].to_string())?;
+        let (out_pwr, out_pwr_status) = parse_fiber_entry(e[4].to_string())?;
+        let (in_pwr, in_pwr_status) = parse_fiber_entry(e[5].to_string())?;
+        /* link budget margins are only reported by some firmware versions */
+        let temp_margin = parse_fiber_margin(e.get(8))?;
+        let tx_margin = parse_fiber_margin(e.get(9))?;
+        let rx_margin = parse_fiber_margin(e.get(10))?;
+
+        let fi = FiberInfo {
+            port: e[0].parse().in_line(&e)?,
+            temperature: temperature,
+            temperature_status: temperature_status.parse().in_line(&e)?,
+            voltage: voltage,
+            voltage_status: voltage_status.parse().in_line(&e)?,
+            current: current,
+            current_status: current_status.parse().in_line(&e)?,
+            output_power: out_pwr,
+            output_power_status: out_pwr_status.parse().in_line(&e)?,
+            input_power: in_pwr,
+            input_power_status: in_pwr_status.parse().in_line(&e)?,
+            present: e[6] == "Insert",
+            link: e[7] == "Normal",
+            temperature_margin: temp_margin.map(|x| (x * 1000.0) as i32),
+            tx_power_margin_db: tx_margin,
+            rx_power_margin_db: rx_margin,
+        };
+        result.push(fi);
+    }
+
+    Ok(result)
+}
+
+/// Parse the output of "show mac address-table"
+fn parse_mac_table(data: &str) -> Result<std::vec::Vec::<MacEntry>, GS1900Error> {
+    let mut result = std::vec::Vec::<MacEntry>::new();
+
+    for e in parse_pipe_table(data, 4, "VID") {
+        let mac = MacEntry {
+            vlan_id: e[0].parse().in_line(&e)?,
+            mac_address: e[1].parse().in_line(&e)?,
+            entry_type: e[2].parse().in_line(&e)?,
+            ports: e[3].to_string(),
+        };
+
+        result.push(mac);
+    }
+
+    Ok(result)
+}
+
+/// Parse the output of "show power inline consumption"
+fn parse_poe_info(data: &str) -> Result<(PoEConfig, std::vec::Vec::<PoESupply>, std::vec::Vec::<PoEPort>), GS1900Error> {
+    let mut step: u8 = 0;
+
+    let mut cfg = PoEConfig::default();
+    let mut supplies = std::vec::Vec::<PoESupply>::new();
+    let mut portdata = std::vec::Vec::<PoEPort>::new();
+
+    for line in data.split("\n") {
+        if line.trim() == "" {
+            step+=1;
+            continue;
+        }
+        match step {
+            0 => {
+                let kv: Vec<&str> = line.split(":").collect();
+                if kv.len() < 2 {
+                    continue;
+                }
+                let key = kv[0].trim();
+                let val = kv[1].trim();
+
+                match key {
+                    "Power management mode" => cfg.management_mode = val.parse().in_line(line)?,
+                    "Pre-allocation" => cfg.pre_allocation = val == "Enabled",
+                    "Power-up sequence" => cfg.power_up_sequence = val.parse().in_line(line)?,
+                    _ => return Err(GS1900Error::parse("Unknown PoE setting", line)),
+                }
+            },
+            1 => {
+                //Unit Power Status Nominal  Allocated       Consumed Available
+                //                  Power    Power           Power    Power
+                //---- ----- ------ -------- --------------- -------- ---------
+                if line.len() < 52 {
+                    continue;
+                }
+                let unit: u8 = match line[0..4].trim().parse() {
+                    Ok(x) => x,
+                    Err(_fail) => { continue; },
+                };
+                let power = line[5..10].trim();
+                let status = line[11..17].trim();
+                let nom_pwr = line[18..26].trim().replace("Watts", "");
+                let alo_pwr = line[27..42].trim().split(" ").collect::<Vec<&str>>()[0].replace("Watts", "");
+                let con_pwr = line[43..51].trim().replace("Watts", "");
+                let ava_pwr = line[52..].trim().replace("Watts", "");
+
+                let supply = PoESupply {
+                    unit: unit,
+                    power: power.parse().in_line(line)?,
+                    status: status.parse().in_line(line)?,
+                    nominal_power: nom_pwr.parse().in_line(line)?,
+                    allocated_power: alo_pwr.parse().in_line(line)?,
+                    consumed_power: con_pwr.parse().in_line(line)?,
+                    available_power: ava_pwr.parse().in_line(line)?,
+                };
+                supplies.push(supply);
+            },
+            2 => {
+                //Port Power Limit (Admin) (mW) Power (mW) Voltage (mV) Current (mA) [Peak (mW)]
+                //---- ------------------------ ---------- ------------ ------------ [----------]
+                if line.len() < 54 {
+                    continue;
+                }
+                let port: u8 = match line[0..4].trim().parse() {
+                    Ok(x) => x,
+                    Err(_fail) => { continue; },
+                };
+                let both_pwr_limit = line[5..29].trim();
+                let pwr_limit_split: Vec<&str> = both_pwr_limit[0..both_pwr_limit.len()-1].split("(").collect();
+                let pwr_limit: i32 = pwr_limit_split[0].trim().parse().in_line(line)?;
+                let admin_pwr_limit: i32 = pwr_limit_split[1].trim().parse().in_line(line)?;
+                let pwr: i32 = line[30..40].trim().parse().in_line(line)?;
+                let volt: i32 = line[41..53].trim().parse().in_line(line)?;
+                let tail: Vec<&str> = line[54..].split_whitespace().collect();
+                let current: i32 = tail.first().unwrap_or(&"").parse().in_line(line)?;
+                let peak: Option<i32> = match tail.get(1) {
+                    Some(x) => Some(x.parse().in_line(line)?),
+                    None => None,
+                };
+
+                let portinfo = PoEPort {
+                    port: port,
+                    power_limit: pwr_limit,
+                    admin_power_limit: admin_pwr_limit,
+                    power: pwr,
+                    voltage: volt,
+                    current: current,
+                    peak_power: peak,
+                };
+                portdata.push(portinfo);
+            },
+            _ => {},
+        }
+    }
+
+    return Ok((cfg, supplies, portdata));
+}
+
+/// Parse the output of "show cable-diag interfaces <ports>"
+fn parse_cable_diag(data: &str) -> Result<std::vec::Vec::<CableDiagnosis>, GS1900Error> {
+    let mut result = std::vec::Vec::<CableDiagnosis>::new();
+
+    let mut diag = CableDiagnosis::default();
+
+    for fields in parse_pipe_table(data, 3, "Port") {
+        if fields.len() == 5 {
+            /* first row of a new port */
+            if diag.port > 0 {
+                result.push(diag);
+            }
+            diag = CableDiagnosis::default();
+
+            let port: u8 = fields[0].parse().in_line(&fields)?;
+            let pair: String = fields[2].replace("Pair ", "").to_string();
+            let pairc: char = pair.chars().next().ok_or_else(|| GS1900Error::parse("Missing cable pair", &fields))?;
+            let length: u32 = fields[3].replace(".", "").parse().in_line(&fields)?;
+            diag.port = port;
+            diag.speed = fields[1].parse().in_line(&fields)?;
+            diag.pair_info[0].pair = pairc;
+            diag.pair_info[0].length = length;
+            diag.pair_info[0].status = fields[4].parse::<CablePairState>().in_line(&fields)?;
+        } else if fields.len() == 3 {
+            let pair: String = fields[0].replace("Pair ", "").to_string();
+            let length: u32 = fields[1].replace(".", "").parse().in_line(&fields)?;
+            let pairc: char = pair.chars().next().ok_or_else(|| GS1900Error::parse("Missing cable pair", &fields))?;
+            let offset = match pairc { 'A' => 0, 'B' => 1, 'C' => 2, 'D' => 3, _ => 4 };
+            if offset > 3 { continue }
+            diag.pair_info[offset].pair = pairc;
+            diag.pair_info[offset].length = length;
+            diag.pair_info[offset].status = fields[2].parse::<CablePairState>().in_line(&fields)?;
+        }
+    }
+
+    if diag.port > 0 {
+        result.push(diag);
+    }
+
+    return Ok(result);
+}
+
+/// Parse the output of "show interfaces <ports>". With status_media_type a missing
+/// media type is accepted, since the caller takes it from the status table.
+fn parse_interfaces(data: &str, status_media_type: bool) -> Result<std::vec::Vec::<InterfaceTrafficStatus>, GS1900Error> {
+    let mut result = std::vec::Vec::<InterfaceTrafficStatus>::new();
+
+    let mut status = InterfaceTrafficStatus::default();
+
+    lazy_static! {
+        static ref RATE: Regex = Regex::new(r"(input|output) rate (\d+) bits/sec, (\d+) packets/sec").unwrap();
+    }
+
+    for line in data.split("\n") {
+        for cap in RATE.captures_iter(line) {
+            let bps = cap[2].parse().in_line(line)?;
+            let pps = cap[3].parse().in_line(line)?;
+            if &cap[1] == "input" {
+                status.input_rate_bps = Some(bps);
+                status.input_rate_pps = Some(pps);
+            } else {
+                status.output_rate_bps = Some(bps);
+                status.output_rate_pps = Some(pps);
+            }
+        }
+
+        if line.starts_with("     ") {
+            lazy_static! {
+                static ref RE1: Regex = Regex::new(r"(\d+) packets input, (\d+) bytes, (\d+) throttles").unwrap();
+                static ref RE2: Regex = Regex::new(r"Received (\d+) broadcasts \((\d+) multicasts\)").unwrap();
+                static ref RE3: Regex = Regex::new(r"(\d+) runts, (\d+) giants, (\d+) throttles").unwrap();
+                static ref RE4: Regex = Regex::new(r"(\d+) input errors, (\d+) CRC, (\d+) frame, (\d+) overrun, (\d+) ignored").unwrap();
+                static ref RE5: Regex = Regex::new(r"(\d+) multicast, (\d+) pause input").unwrap();
+                static ref RE6: Regex = Regex::new(r"(\d+) input packets with dribble condition detected").unwrap();
+                static ref RE7: Regex = Regex::new(r"(\d+) packets output, (\d+) bytes, (\d+) underrun").unwrap();
+                static ref RE8: Regex = Regex::new(r"(\d+) output errors, (\d+) collisions, (\d+) interface resets").unwrap();
+                static ref RE9: Regex = Regex::new(r"(\d+) babbles, (\d+) late collision, (\d+) deferred").unwrap();
+                static ref RE10: Regex = Regex::new(r"(\d+) PAUSE output").unwrap();
+            }
+            for cap in RE1.captures_iter(line) {
+                status.input_packets = cap[1].parse().unwrap();
+                status.input_bytes = cap[2].parse().unwrap();
+                status.input_throttles = cap[3].parse().unwrap();
+            }
+            for cap in RE2.captures_iter(line) {
+                status.input_broadcasts = cap[1].parse().unwrap();
+                status.input_multicasts = cap[2].parse().unwrap();
+            }
+            for cap in RE3.captures_iter(line) {
+                status.input_runts = cap[1].parse().unwrap();
+                status.input_giants = cap[2].parse().unwrap();
+            }
+            for cap in RE4.captures_iter(line) {
+                status.input_errors = cap[1].parse().unwrap();
+                status.input_crc = cap[2].parse().unwrap();
+                status.input_frame = cap[3].parse().unwrap();
+                status.input_overrun = cap[4].parse().unwrap();
+                status.input_ignored = cap[5].parse().unwrap();
+            }
+            for cap in RE5.captures_iter(line) {
+                status.input_pause = cap[2].parse().unwrap();
+            }
+            for cap in RE6.captures_iter(line) {
+                status.input_dribble = cap[1].parse().unwrap();
+            }
+            for cap in RE7.captures_iter(line) {
+                status.output_packets = cap[1].parse().unwrap();
+                status.output_bytes = cap[2].parse().unwrap();
+                status.output_underrun = cap[3].parse().unwrap();
+            }
+            for cap in RE8.captures_iter(line) {
+                status.output_errors = cap[1].parse().unwrap();
+                status.output_collisions = cap[2].parse().unwrap();
+                status.output_interface_resets = cap[3].parse().unwrap();
+            }
+            for cap in RE9.captures_iter(line) {
+                status.output_babbles = cap[1].parse().unwrap();
+                status.output_late_collisions = cap[2].parse().unwrap();
+                status.output_deferred = cap[3].parse().unwrap();
+            }
+            for cap in RE10.captures_iter(line) {
+                status.output_paused =  cap[1].parse().unwrap();
+                if status.port > 0 {
+                    result.push(status);
+                    status = InterfaceTrafficStatus::default();
+                }
+            }
+        } else if line.starts_with("  ") {
+            if line.contains("media type is") {
+                let splitted: Vec<&str> = line.split(", ").collect();
+                status.duplex = splitted[0].trim().replace("-duplex", "").to_string().parse().in_line(line)?;
+                status.speed = splitted[1].trim().replace("-speed", "").to_string().parse().in_line(line)?;
+                let media = splitted.get(2).map(|x| x.trim().trim_start_matches("media type is").trim()).unwrap_or("");
+                match media.parse() {
+                    Ok(x) => status.media_type = x,
+                    /* taken from the status table later on */
+                    Err(_) if status_media_type => {},
+                    Err(e) => return Err(e.into()),
+                }
+            } else {
+                /* e.g. "flow-control is on (negotiated off)" */
+                lazy_static! {
+                    static ref FC: Regex = Regex::new(r"flow-control is (on|off)(?: \(([^)]*)\))?").unwrap();
+                }
+                for cap in FC.captures_iter(line) {
+                    status.flow_control_configured = &cap[1] == "on";
+                    status.flow_control_active = match cap.get(2) {
+                        Some(x) => x.as_str().ends_with(" on") || x.as_str() == "on" || x.as_str() == "active",
+                        None => status.flow_control_configured,
+                    };
+                }
+            }
+        } else if line.starts_with("GigabitEthernet") {
+            let splitted: Vec<&str> = line[15..].split(" ").collect();
+            status.port = splitted[0].parse().in_line(line)?;
+            status.up = splitted[2] == "up";
+        }
+    }
+
+    return Ok(result);
+}
+
+/// Parse the output of "show interfaces utilization"
+fn parse_utilization(data: &str) -> Result<std::vec::Vec::<PortUtilization>, GS1900Error> {
+    let mut result = std::vec::Vec::<PortUtilization>::new();
+
+    for e in parse_pipe_table(data, 3, "Port") {
+        let port = e[0].trim_start_matches(|c: char| c.is_ascii_alphabetic());
+        let percent = |x: &str| x.trim_end_matches('%').trim().parse().in_line(&e);
+        result.push(PortUtilization {
+            port: port.parse().in_line(&e)?,
+            rx_percent: percent(e[1])?,
+            tx_percent: percent(e[2])?,
+        });
+    }
+
+    Ok(result)
+}
+
+/// Parse the output of "show interfaces <ports> status" (is_uplink is not set)
+fn parse_interface_status(data: &str) -> Result<std::vec::Vec::<InterfaceStatus>, GS1900Error> {
+    let mut result = std::vec::Vec::<InterfaceStatus>::new();
+
+    lazy_static! {
+        static ref RE: Regex = Regex::new(r"^(\d+)[ ]+(.*?)[ ]+(notconnect|connected)[ ]+(\d+)[ ]+([^ ]+)[ ]+([^ ]+)[ ]+(Copper|Fiber)$").unwrap();
+    }
+
+    for line in data.split("\n") {
+        for cap in RE.captures_iter(line) {
+            let interface = InterfaceStatus {
+                port: cap[1].parse().unwrap(),
+                name: cap[2].to_string(),
+                connected: &cap[3] == "connected",
+                vlan: cap[4].parse().unwrap(),
+                duplex: cap[5].parse().in_line(line)?,
+                speed: cap[6].parse().in_line(line)?,
+                mediatype: cap[7].parse().in_line(line)?,
+                mode: None,
+                is_uplink: false,
+            };
+            result.push(interface);
+        }
+    }
+
+    Ok(result)
+}
+
+/// Ports listed in the output of "show fiber-transceiver interfaces all"
+fn parse_sfp_ports(data: &str) -> Result<std::vec::Vec<u8>, GS1900Error> {
+    let mut result = std::vec::Vec::new();
+    for e in parse_pipe_table(data, 2, "Port") {
+        result.push(e[0].parse().in_line(&e)?);
+    }
+    Ok(result)
+}
+
+/// Parse the output of "show interfaces <ports> switchport"
+fn parse_switchports(data: &str) -> Result<std::collections::HashMap<u8, Switchport>, GS1900Error> {
+    let mut result = std::collections::HashMap::new();
+
+    let mut port: u8 = 0;
+    for line in data.split("\n") {
+        let kv: Vec<&str> = line.split(" : ").collect();
+        if kv.len() < 2 {
+            continue;
+        }
+
+        let key = kv[0].trim();
+        match key {
+            "Port" => {
+                let num = kv[1].trim().trim_start_matches(|c: char| c.is_ascii_alphabetic());
+                port = num.parse().in_line(line)?;
+            },
+            "Port Mode" if port > 0 => {
+                /* keep the port with its PVID even if the mode is not known */
+                result.insert(port, Switchport { mode: kv[1].trim().parse().ok(), pvid: 0, allowed_vlans: None });
+            },
+            _ if key.contains("NATIVE") || key == "PVID" => {
+                if let Some(sp) = result.get_mut(&port) {
+                    sp.pvid = kv[1].trim().parse().in_line(line)?;
+                }
+            },
+            _ if key.contains("Allowed VLAN") => {
+                if let Some(sp) = result.get_mut(&port) {
+                    sp.allowed_vlans = Some(kv[1].trim().parse().in_line(line)?);
+                }
+            },
+            _ => {},
+        }
+    }
+
+    Ok(result)
+}
+
+/// Parse the output of "show vlan"
+fn parse_vlans(data: &str) -> Result<std::vec::Vec::<VLANInfo>, GS1900Error> {
+    let mut result = std::vec::Vec::<VLANInfo>::new();
+
+    for elements in parse_pipe_table(data, 5, "VID") {
+        let vlan = VLANInfo {
+            id: elements[0].parse().in_line(&elements)?,
+            name: elements[1].to_string(),
+            ports_untagged: elements[2].to_string(),
+            ports_tagged: elements[3].to_string(),
+            members_untagged: elements[2].parse().ok(),
+            members_tagged: elements[3].parse().ok(),
+            vlan_type: elements[4].parse().in_line(&elements)?,
+        };
+
+        result.push(vlan);
+    }
+
+    Ok(result)
+}
+
+/// Parse the output of "show reboot-cause" (Unknown if it has no reboot reason)
+fn parse_reboot_cause(data: &str) -> RebootInfo {
+    let mut result = RebootInfo { cause: RebootCause::Unknown, detail: String::new() };
+
+    for line in data.split("\n") {
+        let kv: Vec<&str> = line.split(" : ").collect();
+        if kv.len() < 2 || !kv[0].contains("Reboot") {
+            continue;
+        }
+
+        result.detail = kv[1].trim().to_string();
+        result.cause = kv[1].trim().parse().unwrap_or(RebootCause::Unknown);
+    }
+
+    result
+}
+
+/// Parse the summary of "ping <ip> count <n>"
+fn parse_ping(data: &str) -> Result<PingResult, GS1900Error> {
+    let mut result = PingResult { transmitted: 0, received: 0, min_ms: 0.0, avg_ms: 0.0, max_ms: 0.0 };
+
+    lazy_static! {
+        static ref RE1: Regex = Regex::new(r"(\d+) packets transmitted, (\d+) (packets )?received").unwrap();
+        static ref RE2: Regex = Regex::new(r"min/avg/max = ([\d.]+)/([\d.]+)/([\d.]+)").unwrap();
+    }
+
+    for line in data.split("\n") {
+        for cap in RE1.captures_iter(line) {
+            result.transmitted = cap[1].parse().in_line(line)?;
+            result.received = cap[2].parse().in_line(line)?;
+        }
+        for cap in RE2.captures_iter(line) {
+            result.min_ms = cap[1].parse().in_line(line)?;
+            result.avg_ms = cap[2].parse().in_line(line)?;
+            result.max_ms = cap[3].parse().in_line(line)?;
+        }
+    }
+
+    Ok(result)
+}
+
+/// Parse the output of "show alarm"
+fn parse_alarms(data: &str) -> Result<std::vec::Vec::<Alarm>, GS1900Error> {
+    let mut result = std::vec::Vec::<Alarm>::new();
+
+    for e in parse_pipe_table(data, 5, "ID") {
+        let alarm = Alarm {
+            id: e[0].parse().in_line(&e)?,
+            severity: e[1].parse().in_line(&e)?,
+            timestamp: e[2].to_string(),
+            description: e[3].to_string(),
+            active: e[4].eq_ignore_ascii_case("active"),
+        };
+
+        result.push(alarm);
+    }
+
+    Ok(result)
+}
+
+impl GS1900 {
+    /// Access the device
+    pub fn new(address: String, username: String, password: String) -> Result<GS1900, GS1900Error> {
+        GS1900::connect(&address, &ConnectionConfig::new(&username, &password))
+    }
+
+    /// Access the device, requiring the exact clear-screen sequence sent
+    /// by the tested firmware versions right before the prompt
+    pub fn new_strict(address: String, username: String, password: String) -> Result<GS1900, GS1900Error> {
+        let mut config = ConnectionConfig::new(&username, &password);
+        config.strict = true;
+        GS1900::connect(&address, &config)
+    }
+
+    /// Access the device, giving up if it does not respond within timeout
+    pub fn new_with_timeout(address: String, username: String, password: String, timeout: std::time::Duration) -> Result<GS1900, GS1900Error> {
+        let mut config = ConnectionConfig::new(&username, &password);
+        config.connect_timeout = timeout;
+        GS1900::connect(&address, &config)
+    }
+
+    /// Access the device using SSH public key authentication. Web features
+    /// are not available, since they require the password.
+    pub fn new_with_key(address: String, username: String, private_key: &std::path::Path, passphrase: Option<&str>) -> Result<GS1900, GS1900Error> {
+        GS1900::connect(&address, &ConnectionConfig::with_key(&username, private_key, passphrase))
+    }
+
+    /// Access the device with the credentials from the GS1900_USER and GS1900_PASS environment
+    /// variables, or GS1900_SSH_KEY (and optionally GS1900_SSH_PASSPHRASE) for key authentication
+    pub fn from_env(address: &str) -> Result<GS1900, GS1900Error> {
+        let var = |name: &str| std::env::var(name).map_err(|_| std::io::Error::new(std::io::ErrorKind::InvalidInput, format!("{} is not set", name)));
+        if let Ok(key) = std::env::var("GS1900_SSH_KEY") {
+            let passphrase = std::env::var("GS1900_SSH_PASSPHRASE").ok();
+            return GS1900::new_with_key(address.to_string(), var("GS1900_USER")?, std::path::Path::new(&key), passphrase.as_deref());
+        }
+        GS1900::new(address.to_string(), var("GS1900_USER")?, var("GS1900_PASS")?)
+    }
+
+    /// Access the device using shared connection parameters
+    pub fn connect(address: &str, config: &ConnectionConfig) -> Result<GS1900, GS1900Error> {
+        let (sess, chan, prompt) = GS1900::open_shell(address, config)?;
+
+        Ok(GS1900 {
+            address: address.to_string(),
+            config: config.clone(),
+            session: sess,
+            channel: chan,
+            prompt,
+            last_output: String::new(),
+            last_command: String::new(),
+            features: std::collections::HashMap::new(),
+            sfp_ports: None,
+            counter_baseline: std::collections::HashMap::new(),
+        })
+    }
+
+    /// Connection parameters used by this instance
+    pub fn config(&self) -> &ConnectionConfig {
+        &self.config
+    }
+
+    fn open_shell(address: &str, config: &ConnectionConfig) -> Result<(ssh2::Session, ssh2::Channel, String), GS1900Error> {
+        let addr = format!("{}:{}", address, config.ssh_port);
+        let mut tcp = Err(std::io::Error::new(std::io::ErrorKind::InvalidInput, format!("Could not resolve {}", address)));
+        for sockaddr in addr.to_socket_addrs()? {
+            tcp = TcpStream::connect_timeout(&sockaddr, config.connect_timeout);
+            if tcp.is_ok() {
+                break;
+            }
+        }
+        let tcp = tcp?;
+
+        let mut sess = Session::new()?;
+        /* fetch_data sets its own timeout for every command */
+        sess.set_timeout(duration_ms(config.connect_timeout));
+        sess.set_tcp_stream(tcp);
+        sess.handshake()?;
+        match (&config.private_key, &config.password) {
+            (Some((key, passphrase)), _) => sess.userauth_pubkey_file(&config.username, None, key, passphrase.as_deref())?,
+            (None, Some(password)) => sess.userauth_password(&config.username, password)?,
+            (None, None) => return Err(GS1900Error::PasswordRequired),
+        }
+
+        let mut chan = sess.channel_session()?;
+        chan.shell()?;
+
+        let prompt = if config.strict {
+            GS1900::read_prompt_strict(&mut chan)?
+        } else {
+            GS1900::read_prompt(&sess, &mut chan)?
+        };
+
+        Ok((sess, chan, prompt))
+    }
+
+    /// Drop the SSH session and log in again
+    pub fn reconnect(&mut self) -> Result<(), GS1900Error> {
+        let (sess, chan, prompt) = GS1900::open_shell(&self.address, &self.config)?;
+        self.session = sess;
+        self.channel = chan;
+        self.prompt = prompt;
+        Ok(())
+    }
+
+    /// Configure how long to wait for more command output before giving up
+    pub fn set_command_timeout(&mut self, timeout: std::time::Duration) {
+        self.config.command_timeout = timeout;
+    }
+
+    /// Configure how often with_retry reconnects and how long it waits before doing so
+    pub fn set_retry(&mut self, retries: u32, backoff: std::time::Duration) {
+        self.config.retries = retries;
+        self.config.retry_backoff = backoff;
+    }
+
+    /// Run f and reconnect and run it again on transport failures
+    ///
+    /// Only SSH errors, timeouts, truncated output and I/O errors caused by
+    /// a broken connection are retried. Everything else (e.g. parse errors,
+    /// invalid arguments or rejected commands) is returned immediately, since
+    /// retrying would just produce the same result.
+    pub fn with_retry<T, F>(&mut self, mut f: F) -> Result<T, GS1900Error>
+        where F: FnMut(&mut GS1900) -> Result<T, GS1900Error> {
+        let mut attempt = 0;
+        loop {
+            let e = match f(self) {
+                Ok(x) => return Ok(x),
+                Err(e) => e,
+            };
+
+            let transient = match e {
+                GS1900Error::Ssh(_) | GS1900Error::Truncated { .. } | GS1900Error::Timeout { .. } => true,
+                GS1900Error::Io(ref e) => matches!(e.kind(),
+                    std::io::ErrorKind::ConnectionReset | std::io::ErrorKind::ConnectionAborted |
+                    std::io::ErrorKind::BrokenPipe | std::io::ErrorKind::UnexpectedEof |
+                    std::io::ErrorKind::TimedOut),
+                _ => false,
+            };
+            if !transient || attempt >= self.config.retries {
+                return Err(e);
+            }
+
+            attempt += 1;
+            debug!("retry {}/{} after error: {}", attempt, self.config.retries, e);
+            std::thread::sleep(self.config.retry_backoff * attempt);
+            self.reconnect()?;
+        }
+    }
+
+    fn read_prompt_strict(chan: &mut ssh2::Channel) -> Result<String, GS1900Error> {
+        let mut clearbuffer = [0; 7];
+        chan.read_exact(&mut clearbuffer)?;
+
+        if clearbuffer != [27, 91, 72, 27, 91, 74, 0] {
+            return Err(GS1900Error::UnexpectedPrompt);
+        }
+
+        let mut prompt = [0; 32];
+        let len = chan.read(&mut prompt)?;
+
+        Ok(String::from_utf8_lossy(&prompt[0..len]).to_string())
+    }
+
+    /// Skip banner and terminal control sequences until the prompt appears
+    fn read_prompt(sess: &ssh2::Session, chan: &mut ssh2::Channel) -> Result<String, GS1900Error> {
+        sess.set_timeout(1000);
+        let deadline = std::time::Instant::now() + std::time::Duration::from_secs(10);
+        let mut data = String::new();
+
+        while std::time::Instant::now() < deadline {
+            let mut buffer = [0; 100];
+            match chan.read(&mut buffer) {
+                Ok(0) => break,
+                Ok(len) => data += &String::from_utf8_lossy(&buffer[0..len]),
+                Err(_e) => {},
+            }
+
+            if let Some(prompt) = find_prompt(&data) {
+                return Ok(prompt);
+            }
+        }
+
+        Err(GS1900Error::UnexpectedPrompt)
+    }
+
+    /// Check if the switch supports a feature (probed once, then cached)
+    pub fn supports(&mut self, feature: Feature) -> bool {
+        if let Some(x) = self.features.get(&feature) {
+            return *x;
+        }
+
+        let result = match feature {
+            Feature::PoE => self.probe("show power inline consumption"),
+            Feature::SFP => self.interface_status_int("all").map(|x| x.iter().any(|p| matches!(p.mediatype, MediaType::Fiber))),
+            Feature::Routing => self.probe("show ip route static"),
+            Feature::LoopGuard => self.probe("show loop-guard"),
+            Feature::LEDMode => self.probe("show led"),
+            Feature::DHCPSnooping => self.probe("show ip dhcp snooping"),
+        };
+
+        match result {
+            Ok(x) => {
+                self.features.insert(feature, x);
+                x
+            },
+            /* transport failure, do not cache */
+            Err(_) => false,
+        }
+    }
+
+    /// Run a command and check if the switch accepts it
+    fn probe(&mut self, cmd: &str) -> Result<bool, GS1900Error> {
+        match self.command(cmd) {
+            Ok(_) => Ok(true),
+            Err(GS1900Error::CommandRejected { .. }) => Ok(false),
+            Err(e) => Err(e),
+        }
+    }
+
+    /// Switch from user mode (">" prompt) to privileged mode ("#" prompt)
+    pub fn enable(&mut self, password: Option<&str>) -> Result<(), GS1900Error> {
+        if self.is_privileged() {
+            return Ok(());
+        }
+
+        self.write_command("enable")?;
+        let mut prompt = GS1900::read_prompt(&self.session, &mut self.channel)?;
+
+        if prompt.trim_end().ends_with("Password:") {
+            /* not sent via write_command to keep the password out of the log */
+            self.write_line(password.unwrap_or(""))?;
+            prompt = GS1900::read_prompt(&self.session, &mut self.channel)?;
+
+            /* wrong password: abort the remaining password prompts */
+            for _ in 0..3 {
+                if !prompt.trim_end().ends_with("Password:") {
+                    break;
+                }
+                self.write_line("")?;
+                prompt = GS1900::read_prompt(&self.session, &mut self.channel)?;
+            }
+        }
+
+        self.prompt = prompt;
+        if !self.is_privileged() {
+            return Err(GS1900Error::NotPrivileged);
+        }
+        Ok(())
+    }
+
+    fn is_privileged(&self) -> bool {
+        self.prompt.trim_end().ends_with('#')
+    }
+
+    /// Configure connect and request timeouts used by the HTTP commands
+    #[cfg(feature = "web")]
+    pub fn set_http_timeouts(&mut self, connect: std::time::Duration, request: std::time::Duration) {
+        self.config.http_connect_timeout = connect;
+        self.config.http_timeout = request;
+    }
+
+    fn fetch_data(&mut self) -> Result<String, GS1900Error> {
+        self.fetch_data_timeout(duration_ms(self.config.command_timeout))
+    }
+
+    /// Read command output until the prompt appears, waiting up to timeout_ms between chunks
+    fn fetch_data_timeout(&mut self, timeout_ms: u32) -> Result<String, GS1900Error> {
+        self.fetch_data_progress(timeout_ms, &mut |_| {})
+    }
+
+    /// Like fetch_data_timeout, but pass every received chunk to progress as it arrives
+    fn fetch_data_progress(&mut self, timeout_ms: u32, progress: &mut dyn FnMut(&str)) -> Result<String, GS1900Error> {
+        let data = self.fetch_raw(timeout_ms, progress)?;
+        if let Err(e) = check_output(&self.last_command, &data) {
+            /* keep the rejected output available via last_output() */
+            self.clean_data(data);
+            return Err(e);
+        }
+        Ok(data)
+    }
+
+    /// Like fetch_data_timeout, but without checking for CLI error messages
+    fn fetch_raw(&mut self, timeout_ms: u32, progress: &mut dyn FnMut(&str)) -> Result<String, GS1900Error> {
+        let mut shell = SshShell { session: &self.session, channel: &mut self.channel };
+        read_output(&mut shell, &self.prompt, &self.last_command, std::time::Duration::from_millis(u64::from(timeout_ms)), progress)
+    }
+
+    fn truncated(&self, data: &str) -> GS1900Error {
+        truncated(&self.last_command, data)
+    }
+
+    fn is_prompt(&self, line: &str) -> bool {
+        is_prompt(line, &self.prompt)
+    }
+
+    fn clean_data(&mut self, data: String) -> String {
+        let result = clean_output(&data, &self.prompt);
+        trace!("response: {:?}", result);
+        self.last_output = result.clone();
+        return result;
+    }
+
+    /// Cleaned output of the last command, useful to debug parser failures
+    pub fn last_output(&self) -> &str {
+        self.last_output.as_str()
+    }
+
+    pub fn basic_info(&mut self) -> Result<BasicInfo, GS1900Error> {
+        self.write_command("show info")?;
+
+        let raw = self.fetch_data()?;
+        let data = self.clean_data(raw);
+
+        parse_basic_info(&data)
+    }
+
+    /// Set the system name (hostname)
+    pub fn set_system_name(&mut self, name: &str) -> Result<(), GS1900Error> {
+        let name = quote_arg(name)?;
+        self.configure(&[format!("hostname {}", name)])
+    }
+
+    /// Set the system location
+    pub fn set_system_location(&mut self, location: &str) -> Result<(), GS1900Error> {
+        let location = quote_arg(location)?;
+        self.configure(&[format!("system location {}", location)])
+    }
+
+    /// Set the system contact
+    pub fn set_system_contact(&mut self, contact: &str) -> Result<(), GS1900Error> {
+        let contact = quote_arg(contact)?;
+        self.configure(&[format!("system contact {}", contact)])
+    }
+
+    pub fn lldp_info(&mut self) -> Result<std::vec::Vec::<LLDPNeighbor>, GS1900Error> {
+        self.write_command("show lldp neighbor")?;
+
+        let raw = self.fetch_data()?;
+        let data = self.clean_data(raw);
+
+        parse_lldp_neighbors(&data)
+    }
+
+    /// Global LLDP settings and the transmit/receive setting of every port
+    pub fn lldp_config(&mut self) -> Result<LLDPConfig, GS1900Error> {
+        self.write_command("show lldp")?;
+        let mut result = LLDPConfig { enabled: false, tx_interval: 0, ttl: 0, ports: std::vec::Vec::new() };
+
+        let raw = self.fetch_data()?;
+        let data = self.clean_data(raw);
+
+        let mut hold_multiplier = None;
+        for line in data.split("\n") {
+            let kv: Vec<&str> = line.splitn(2, ':').collect();
+            if kv.len() < 2 {
+                continue;
+            }
+
+            let key = kv[0].trim();
+            /* values may carry a unit, e.g. "30 seconds" */
+            let val = kv[1].split_whitespace().next().unwrap_or("");
+            let num = || val.parse::<u32>().in_line(line);
+            match key {
+                "State" | "LLDP Status" | "LLDP State" => result.enabled = val.eq_ignore_ascii_case("enabled"),
+                "Timer" | "Tx Interval" | "Transmit Interval" => result.tx_interval = num()?,
+                "Hold multiplier" | "Hold Multiplier" => hold_multiplier = Some(num()?),
+                "TTL" | "Hold Time" => result.ttl = num()?,
+                _ => {},
+            }
+        }
+        if let (0, Some(hold)) = (result.ttl, hold_multiplier) {
+            result.ttl = result.tx_interval * hold;
+        }
+
+        for e in parse_pipe_table(&data, 2, "Port") {
+            if e[0].starts_with('-') {
+                continue;
+            }
+            let port = e[0].trim_start_matches(|c: char| c.is_ascii_alphabetic());
+            result.ports.push(LLDPPortConfig {
+                port: port.parse().in_line(&e)?,
+                admin_status: e[1].parse().in_line(&e)?,
+            });
+        }
+
+        Ok(result)
+    }
+
+    /// Set the LLDP transmit/receive setting of a port
+    pub fn set_lldp_admin_status(&mut self, port: u8, status: LLDPAdminStatus) -> Result<(), GS1900Error> {
+        let (tx, rx) = match status {
+            LLDPAdminStatus::TxRx => (true, true),
+            LLDPAdminStatus::TxOnly => (true, false),
+            LLDPAdminStatus::RxOnly => (false, true),
+            LLDPAdminStatus::Disabled => (false, false),
+        };
+        let tx = if tx { "lldp tx" } else { "no lldp tx" };
+        let rx = if rx { "lldp rx" } else { "no lldp rx" };
+
+        self.configure_interface(port, &[tx.to_string(), rx.to_string()])
+    }
+
+    pub fn fiber_info(&mut self) -> Result<std::vec::Vec::<FiberInfo>, GS1900Error> {
+        self.write_command("show fiber-transceiver interfaces all")?;
+
+        let raw = self.fetch_data()?;
+        let data = self.clean_data(raw);
+
+        parse_fiber_info(&data)
+    }
+
+    pub fn mac_table(&mut self) -> Result<std::vec::Vec::<MacEntry>, GS1900Error> {
+        self.write_command("show mac address-table")?;
+
+        let raw = self.fetch_data()?;
+        let data = self.clean_data(raw);
+
+        parse_mac_table(&data)
+    }
+
+    /// Entries added to or removed from the MAC address table since a previous mac_table() call
+    ///
+    /// Entries are matched by MAC address and VLAN.
+    pub fn mac_table_changes(&mut self, previous: &[MacEntry]) -> Result<MacTableDelta, GS1900Error> {
+        let current = self.mac_table()?;
+
+        let old: std::collections::HashSet<(MacAddress, u16)> = previous.iter().map(|x| (x.mac_address, x.vlan_id)).collect();
+        let new: std::collections::HashSet<(MacAddress, u16)> = current.iter().map(|x| (x.mac_address, x.vlan_id)).collect();
+
+        Ok(MacTableDelta {
+            added: current.iter().filter(|x| !old.contains(&(x.mac_address, x.vlan_id))).cloned().collect(),
+            removed: previous.iter().filter(|x| !new.contains(&(x.mac_address, x.vlan_id))).cloned().collect(),
+        })
+    }
+
+    pub fn mac_table_port(&mut self, port: u8) -> Result<std::vec::Vec::<MacEntry>, GS1900Error> {
+        self.write_command(format!("show mac address-table interfaces {}", port).as_str())?;
+
+        let raw = self.fetch_data()?;
+        let data = self.clean_data(raw);
+
+        parse_mac_table(&data)
+    }
+
+    pub fn mac_table_vlan(&mut self, vlan: u32) -> Result<std::vec::Vec::<MacEntry>, GS1900Error> {
+        if !(1..=4094).contains(&vlan) {
+            return Err(std::io::Error::new(std::io::ErrorKind::InvalidInput, "Invalid VLAN ID").into());
+        }
+
+        self.write_command(format!("show mac address-table vlan {}", vlan).as_str())?;
+
+        let raw = self.fetch_data()?;
+        let data = self.clean_data(raw);
+
+        parse_mac_table(&data)
+    }
+
+    pub fn lookup_mac_address(&mut self, address: MacAddress) -> Result<std::option::Option<MacEntry>, GS1900Error> {
+        self.write_command(format!("show mac address-table {}", address).as_str())?;
+
+        let raw = self.fetch_data()?;
+        let data = self.clean_data(raw);
+
+        Ok(parse_mac_table(&data)?.into_iter().next())
+    }
+
+    /// Remove dynamically learned MAC addresses (of one port or all ports), so
+    /// that the switch learns them again. Static and management entries are
+    /// kept by the switch.
+    pub fn flush_dynamic_mac(&mut self, port: Option<u8>) -> Result<(), GS1900Error> {
+        self.check_writable()?;
+        let cmd = match port {
+            Some(p) => format!("clear mac address-table dynamic interfaces {}", p),
+            None => "clear mac address-table dynamic".to_string(),
+        };
+        self.command(cmd.as_str())?;
+        Ok(())
+    }
+
+    pub fn poe_debug(&mut self) -> Result<std::vec::Vec::<PoEDebug>, GS1900Error> {
+        self.write_command("debug ilpower port status")?;
+        let mut result = std::vec::Vec::<PoEDebug>::new();
+
+        let raw = self.fetch_data()?;
+        let data = self.clean_data(raw);
+
+        for line in data.split("\n") {
+            if line.len() < 39 {
+                continue;
+            }
+            let port = line[0..4].trim().to_string();
+            let _state = line[5..10].trim().to_string();
+            let status = line[11..21].trim().to_string();
+            let prio = line[22..30].trim().to_string();
+            let class = line[31..38].trim().to_string();
+            let reason = line[39..].trim().to_string();
+
+            if port.len() < 1 || port == "Port" || port == "----" {
+                continue;
+            }
+
+            let info = PoEDebug {
+                port: port.parse().in_line(line)?,
+                status: status.parse().in_line(line)?,
+                priority: prio.parse().in_line(line)?,
+                class: class.parse().in_line(line)?,
+                reason,
+            };
+
+            result.push(info);
+        }
+        Ok(result)
+    }
+
+    pub fn poe_info(&mut self) -> Result<(PoEConfig, std::vec::Vec::<PoESupply>, std::vec::Vec::<PoEPort>), GS1900Error> {
+        self.write_command("show power inline consumption")?;
+
+        let raw = self.fetch_data()?;
+        let data = self.clean_data(raw);
+
+        parse_poe_info(&data)
+    }
+
+    /// Reset the traffic counters of one port (or all ports if None). This includes
+    /// the fiber link counters and the baseline used by interface_info_delta.
+    /// Unknown ports are reported as CommandRejected.
+    pub fn clear_interface_counters(&mut self, port: Option<u8>) -> Result<(), GS1900Error> {
+        self.check_writable()?;
+        let interfaces = match port {
+            Some(p) => format!("{}", p),
+            None => "all".to_string(),
+        };
+
+        self.command_confirmed(format!("clear interfaces {} counters", interfaces).as_str())?;
+
+        /* only available on models with SFP ports */
+        match self.command_confirmed(format!("clear fiber-transceiver interfaces {} counters", interfaces).as_str()) {
+            Err(GS1900Error::CommandRejected { .. }) => {},
+            x => { x?; },
+        }
+
+        match port {
+            Some(p) => { self.counter_baseline.remove(&p); },
+            None => self.counter_baseline.clear(),
+        }
+        Ok(())
+    }
+
+    /// Reset the tracked peak power consumption of a PoE port
+    pub fn reset_poe_peak(&mut self, port: u8) -> Result<(), GS1900Error> {
+        self.check_writable()?;
+        self.command(format!("clear power inline peak interfaces {}", port).as_str())?;
+        Ok(())
+    }
+
+    pub fn cable_info(&mut self) -> Result<std::vec::Vec::<CableDiagnosis>, GS1900Error> {
+        return self.cable_info_int("all");
+    }
+
+    pub fn cable_info_port(&mut self, port: u8) -> Result<std::option::Option<CableDiagnosis>, GS1900Error> {
+        let res = self.cable_info_int(format!("{}", port).as_str());
+        return match res {
+            Ok(x) => {
+                if x.len() <= 0 {
+                    return Ok(None);
+                }
+                let e = x[0];
+                return Ok(Some(e));
+            },
+            Err(e) => Err(e),
+        };
+    }
+
+    /// Cable diagnosis for a set of ports (empty if ports is empty)
+    pub fn cable_info_ports(&mut self, ports: &[u8]) -> Result<std::vec::Vec::<CableDiagnosis>, GS1900Error> {
+        if ports.is_empty() {
+            return Ok(std::vec::Vec::new());
+        }
+        self.cable_info_int(PortRange::new(ports).to_string().as_str())
+    }
+
+    fn cable_info_int(&mut self, interfaces: &str) -> Result<std::vec::Vec::<CableDiagnosis>, GS1900Error> {
+        self.write_command(format!("show cable-diag interfaces {}", interfaces).as_str())?;
+
+        let raw = self.fetch_data()?;
+        let data = self.clean_data(raw);
+
+        parse_cable_diag(&data)
+    }
+
+    pub fn interface_info(&mut self) -> Result<std::vec::Vec::<InterfaceTrafficStatus>, GS1900Error> {
+        self.interface_info_ext(false)
+    }
+
+    /// Like interface_info, optionally taking the media type from the interface
+    /// status table, which is reliable for combo ports (costs one extra command)
+    pub fn interface_info_ext(&mut self, status_media_type: bool) -> Result<std::vec::Vec::<InterfaceTrafficStatus>, GS1900Error> {
+        let mut result = self.interface_info_int("all", status_media_type)?;
+
+        if status_media_type {
+            let status = self.interface_status_int("all")?;
+            for entry in result.iter_mut() {
+                if let Some(x) = status.iter().find(|x| x.port == entry.port) {
+                    entry.media_type = x.mediatype;
+                }
+            }
+        }
+
+        Ok(result)
+    }
+
+    pub fn interface_info_port(&mut self, port: u8) -> Result<InterfaceTrafficStatus, GS1900Error> {
+        let ret = self.interface_info_int(format!("{}", port).as_str(), false);
+        return match ret {
+            Err(x) => Err(x),
+            Ok(x) => {
+                if x.is_empty() {
+                    Err(GS1900Error::parse(format!("Port {} not found", port), ""))
+                } else {
+                    Ok(x[0])
+                }
+            },
+        }
+    }
+
+    /// Maximum frame size accepted by the switch (in bytes)
+    pub fn mtu(&mut self) -> Result<u32, GS1900Error> {
+        self.write_command("show jumbo-frame")?;
+
+        let raw = self.fetch_data()?;
+        let data = self.clean_data(raw);
+
+        for line in data.split("\n") {
+            let kv: Vec<&str> = line.split(" : ").collect();
+            if kv.len() < 2 || !kv[0].contains("Jumbo") && !kv[0].contains("Frame Size") {
+                continue;
+            }
+
+            let val = kv[1].trim().trim_end_matches("bytes").trim();
+            return val.parse().map_err(|_| GS1900Error::Parse { context: "jumbo frame size".to_string(), line: line.to_string() });
+        }
+
+        Err(GS1900Error::parse("Missing jumbo frame size", ""))
+    }
+
+    /// Configured frame size together with the giant/runt counters of a port.
+    /// The counters are taken from interface_info_delta, i.e. they count since
+    /// the previous delta call for the port (or since the counters were cleared).
+    /// A MTU mismatch is assumed if at least 0.1% of the frames received in
+    /// that period were giants, so a few old giants do not mark the port forever.
+    pub fn jumbo_diagnosis(&mut self, port: u8) -> Result<JumboDiagnosis, GS1900Error> {
+        let mtu = self.mtu()?;
+        let status = self.interface_info_delta(port)?;
+
+        Ok(JumboDiagnosis {
+            port,
+            mtu,
+            input_giants: status.input_giants,
+            input_runts: status.input_runts,
+            likely_mtu_mismatch: status.input_giants > 0 && status.input_giants * 1000 >= status.input_packets,
+        })
+    }
+
+    /// Counter increase of a port since the previous call for the same port on this
+    /// handle. The first call for a port returns the absolute counters and stores
+    /// them as baseline. Non-counter fields (link state, rates, ...) are current values.
+    /// The baseline is kept across reconnects, so counters cleared in between show up
+    /// as wrapped (very large) deltas.
+    pub fn interface_info_delta(&mut self, port: u8) -> Result<InterfaceTrafficStatus, GS1900Error> {
+        let current = self.interface_info_port(port)?;
+
+        let result = match self.counter_baseline.get(&port) {
+            Some(old) => current.counter_delta(old),
+            None => current,
+        };
+        self.counter_baseline.insert(port, current);
+
+        Ok(result)
+    }
+
+    /// Recent link utilization of every port
+    pub fn interface_utilization(&mut self) -> Result<std::vec::Vec::<PortUtilization>, GS1900Error> {
+        self.write_command("show interfaces utilization")?;
+
+        let raw = match self.fetch_data() {
+            Err(GS1900Error::CommandRejected { .. }) => {
+                return Err(GS1900Error::Unsupported { feature: "interface utilization".to_string() });
+            },
+            x => x?,
+        };
+        let data = self.clean_data(raw);
+
+        parse_utilization(&data)
+    }
+
+    /// Current broadcast/multicast receive rates, measured over the given interval
+    pub fn storm_rates(&mut self, interval: std::time::Duration) -> Result<std::vec::Vec::<StormRates>, GS1900Error> {
+        let first = self.interface_info()?;
+        let start = std::time::Instant::now();
+        std::thread::sleep(interval);
+        let second = self.interface_info()?;
+        let elapsed = start.elapsed();
+
+        Ok(second.iter().filter_map(|new| {
+            first.iter().find(|old| old.port == new.port).map(|old| StormRates {
+                port: new.port,
+                bcast_pps: counter_rate(old.input_broadcasts, new.input_broadcasts, elapsed),
+                mcast_pps: counter_rate(old.input_multicasts, new.input_multicasts, elapsed),
+            })
+        }).collect())
+    }
+
+    fn interface_info_int(&mut self, interfaces: &str, status_media_type: bool) -> Result<std::vec::Vec::<InterfaceTrafficStatus>, GS1900Error> {
+        self.write_command(format!("show interfaces {}", interfaces).as_str())?;
+
+        let raw = self.fetch_data()?;
+        let data = self.clean_data(raw);
+
+        parse_interfaces(&data, status_media_type)
+    }
+
+    /// Per egress queue counters of a port (empty if not supported by the firmware)
+    pub fn queue_stats(&mut self, port: u8) -> Result<std::vec::Vec::<QueueStat>, GS1900Error> {
+        self.write_command(format!("show qos queue statistics interfaces {}", port).as_str())?;
+        let mut result = std::vec::Vec::<QueueStat>::new();
+
+        let raw = match self.fetch_data() {
+            Err(GS1900Error::CommandRejected { .. }) => return Ok(result),
+            x => x?,
+        };
+        let data = self.clean_data(raw);
+
+        for e in parse_pipe_table(&data, 3, "Queue") {
+            let stat = QueueStat {
+                queue: e[0].parse().in_line(&e)?,
+                enqueued: e[1].parse().in_line(&e)?,
+                dropped: e[2].parse().in_line(&e)?,
+            };
+            result.push(stat);
+        }
+
+        Ok(result)
+    }
+
+    pub fn interface_status_info(&mut self) -> Result<std::vec::Vec::<InterfaceStatus>, GS1900Error> {
+        let mut result = self.interface_status_int("all")?;
+        let switchports = self.switchports("all")?;
+
+        for interface in result.iter_mut() {
+            if let Some(sp) = switchports.get(&interface.port) {
+                interface.mode = sp.mode;
+            }
+        }
+
+        Ok(result)
+    }
+
+    /// Status of a single port (None if the port does not exist)
+    pub fn interface_status_info_port(&mut self, port: u8) -> Result<Option<InterfaceStatus>, GS1900Error> {
+        let status = match self.interface_status_int(format!("{}", port).as_str()) {
+            /* invalid port numbers are rejected by the CLI */
+            Err(GS1900Error::CommandRejected { .. }) => return Ok(None),
+            x => x?,
+        };
+        let mut result = match status.into_iter().find(|x| x.port == port) {
+            Some(x) => x,
+            None => return Ok(None),
+        };
+
+        if let Some(sp) = self.switchports(format!("{}", port).as_str())?.get(&port) {
+            result.mode = sp.mode;
+        }
+
+        Ok(Some(result))
+    }
+
+    /// Poll the status of a port until its link is up (or down)
+    pub fn wait_for_link(&mut self, port: u8, up: bool, timeout: std::time::Duration) -> Result<InterfaceStatus, GS1900Error> {
+        let deadline = std::time::Instant::now() + timeout;
+
+        loop {
+            let status = match self.interface_status_info_port(port)? {
+                Some(x) => x,
+                None => return Err(std::io::Error::new(std::io::ErrorKind::InvalidInput, format!("Unknown port {}", port)).into()),
+            };
+
+            if status.connected == up {
+                return Ok(status);
+            }
+
+            if std::time::Instant::now() >= deadline {
+                let state = if up { "up" } else { "down" };
+                return Err(GS1900Error::Timeout { operation: format!("waiting for link {} on port {}", state, port) });
+            }
+
+            std::thread::sleep(std::time::Duration::from_secs(1));
+        }
+    }
+
+    /// Link state of every port joined with the LLDP neighbor information
+    pub fn topology(&mut self) -> Result<std::vec::Vec::<PortTopology>, GS1900Error> {
+        let status = self.interface_status_int("all")?;
+        let mut neighbors = self.lldp_info()?;
+
+        Ok(status.into_iter().map(|x| {
+            let neighbor = neighbors.iter().position(|n| n.port == x.port).map(|i| neighbors.remove(i));
+            PortTopology { port: x.port, connected: x.connected, speed: x.speed, neighbor }
+        }).collect())
+    }
+
+    /// Advertised and link partner auto-negotiation capabilities of every port
+    pub fn port_autoneg(&mut self) -> Result<std::vec::Vec::<AutonegStatus>, GS1900Error> {
+        self.write_command("show interfaces all autonegotiation")?;
+        let mut result = std::vec::Vec::<AutonegStatus>::new();
+
+        let raw = self.fetch_data()?;
+        let data = self.clean_data(raw);
+
+        let parse_list = |x: &str| -> std::io::Result<std::vec::Vec<LinkSpeed>> {
+            x.split(',').map(|x| x.trim()).filter(|x| !x.is_empty() && *x != "N/A" && *x != "-").map(|x| x.parse()).collect()
+        };
+
+        for line in data.split("\n") {
+            let kv: Vec<&str> = line.split(" : ").collect();
+            if kv.len() < 2 {
+                continue;
+            }
+
+            match kv[0].trim() {
+                "Port" => {
+                    let num = kv[1].trim().trim_start_matches(|c: char| c.is_ascii_alphabetic());
+                    let port = num.parse().in_line(line)?;
+                    result.push(AutonegStatus { port, advertised: std::vec::Vec::new(), partner: std::vec::Vec::new() });
+                },
+                "Advertised" | "Local Advertisement" => {
+                    if let Some(x) = result.last_mut() {
+                        x.advertised = parse_list(kv[1])?;
+                    }
+                },
+                "Partner" | "Link Partner Advertisement" => {
+                    if let Some(x) = result.last_mut() {
+                        x.partner = parse_list(kv[1])?;
+                    }
+                },
+                _ => {},
+            }
+        }
+
+        Ok(result)
+    }
+
+    /// Running configuration block of a single port
+    pub fn port_config(&mut self, port: u8) -> Result<String, GS1900Error> {
+        let data = match self.command(format!("show running-config interfaces GigabitEthernet {}", port).as_str()) {
+            /* SFP+ uplink ports */
+            Err(GS1900Error::CommandRejected { .. }) => {
+                self.command(format!("show running-config interfaces TenGigabitEthernet {}", port).as_str())?
+            },
+            x => x?,
+        };
+
+        /* drop the command echo and anything else before the interface block */
+        let lines: Vec<&str> = data.split('\n').map(|x| x.trim_end_matches('\r')).collect();
+        let start = match lines.iter().position(|x| x.starts_with("interface ")) {
+            Some(x) => x,
+            None => return Err(GS1900Error::parse(format!("No configuration for port {}", port), "")),
+        };
+
+        Ok(lines[start..].join("\n").trim_end().to_string())
+    }
+
+    /// Cheap port inventory based on the interface status table only
+    pub fn port_map(&mut self) -> Result<std::vec::Vec::<PortDescriptor>, GS1900Error> {
+        let status = self.interface_status_int("all")?;
+
+        Ok(status.into_iter().map(|x| PortDescriptor { port: x.port, media: x.mediatype, name: x.name }).collect())
+    }
+
+    /// Description of every port (empty if none is configured)
+    pub fn port_descriptions(&mut self) -> Result<std::collections::HashMap<u8, String>, GS1900Error> {
+        self.write_command("show interfaces all description")?;
+        let mut result = std::collections::HashMap::new();
+
+        lazy_static! {
+            static ref RE: Regex = Regex::new(r"^\s*(?:gi)?(\d+)(?:\s+(.*?))?\s*$").unwrap();
+        }
+
+        let raw = self.fetch_data()?;
+        let data = self.clean_data(raw);
+
+        for line in data.split("\n") {
+            for cap in RE.captures_iter(line) {
+                let port: u8 = cap[1].parse().in_line(line)?;
+                let description = cap.get(2).map_or("", |x| x.as_str());
+                result.insert(port, description.to_string());
+            }
+        }
+
+        Ok(result)
+    }
+
+    fn interface_status_int(&mut self, interfaces: &str) -> Result<std::vec::Vec::<InterfaceStatus>, GS1900Error> {
+        self.write_command(format!("show interfaces {} status", interfaces).as_str())?;
+
+        let raw = self.fetch_data()?;
+        let data = self.clean_data(raw);
+
+        let mut result = parse_interface_status(&data)?;
+
+        let sfp_ports = self.sfp_ports()?;
+        for interface in result.iter_mut() {
+            interface.is_uplink = sfp_ports.contains(&interface.port);
+        }
+
+        Ok(result)
+    }
+
+    /// Ports with an SFP cage, i.e. the ports listed in the fiber transceiver table (probed once, then cached)
+    fn sfp_ports(&mut self) -> Result<std::vec::Vec<u8>, GS1900Error> {
+        if let Some(x) = &self.sfp_ports {
+            return Ok(x.clone());
+        }
+
+        self.write_command("show fiber-transceiver interfaces all")?;
+        let data = match self.fetch_data() {
+            Ok(raw) => self.clean_data(raw),
+            /* models without SFP ports do not know the command */
+            Err(GS1900Error::CommandRejected { .. }) => String::new(),
+            Err(e) => return Err(e),
+        };
+
+        let result = parse_sfp_ports(&data)?;
+        self.sfp_ports = Some(result.clone());
+        Ok(result)
+    }
+
+    /// Fail with InvalidInput if the switch does not have the port
+    fn check_port(&mut self, port: u8) -> Result<(), GS1900Error> {
+        match self.switchports(&port.to_string()) {
+            Ok(x) if x.contains_key(&port) => Ok(()),
+            Ok(_) | Err(GS1900Error::CommandRejected { .. }) => {
+                Err(std::io::Error::new(std::io::ErrorKind::InvalidInput, format!("Unknown port {}", port)).into())
+            },
+            Err(e) => Err(e),
+        }
+    }
+
+    fn switchports(&mut self, interfaces: &str) -> Result<std::collections::HashMap<u8, Switchport>, GS1900Error> {
+        self.write_command(format!("show interfaces {} switchport", interfaces).as_str())?;
+
+        let raw = self.fetch_data()?;
+        let data = self.clean_data(raw);
+
+        parse_switchports(&data)
+    }
+
+    /// Allowed VLANs of every trunk port
+    pub fn trunk_allowed_vlans(&mut self) -> Result<std::vec::Vec::<(u8, AllowedVLANs)>, GS1900Error> {
+        let switchports = self.switchports("all")?;
+
+        let mut result: std::vec::Vec::<(u8, AllowedVLANs)> = switchports.into_iter()
+            .filter(|(_, sp)| matches!(sp.mode, Some(PortMode::Trunk)))
+            .map(|(port, sp)| (port, sp.allowed_vlans.unwrap_or(AllowedVLANs::All)))
+            .collect();
+        result.sort_by_key(|x| x.0);
+
+        Ok(result)
+    }
+
+    /// Set the default (access/native) VLAN of a port
+    pub fn set_port_pvid(&mut self, port: u8, vlan: u16) -> Result<(), GS1900Error> {
+        if !(1..=4094).contains(&vlan) {
+            return Err(std::io::Error::new(std::io::ErrorKind::InvalidInput, "Invalid VLAN ID").into());
+        }
+        if !self.vlan_info()?.iter().any(|v| v.id == u32::from(vlan)) {
+            return Err(std::io::Error::new(std::io::ErrorKind::InvalidInput, format!("VLAN {} does not exist", vlan)).into());
+        }
+
+        let mode = match self.switchports(&port.to_string())?.get(&port) {
+            Some(Switchport { mode: Some(mode), .. }) => *mode,
+            Some(_) => return Err(std::io::Error::new(std::io::ErrorKind::InvalidInput, format!("Unknown mode of port {}", port)).into()),
+            None => return Err(std::io::Error::new(std::io::ErrorKind::InvalidInput, format!("Unknown port {}", port)).into()),
+        };
+        let cmd = match mode {
+            PortMode::Access => format!("switchport access vlan {}", vlan),
+            PortMode::Trunk => format!("switchport trunk native vlan {}", vlan),
+            PortMode::Hybrid => format!("switchport hybrid pvid {}", vlan),
+        };
+        self.configure(&[format!("interface {}", port), cmd, "exit".to_string()])?;
+
+        match self.switchports(&port.to_string())?.get(&port) {
+            Some(sp) if sp.pvid == vlan => Ok(()),
+            _ => Err(std::io::Error::other("PVID change was not applied").into()),
+        }
+    }
+
+    pub fn vlan_info(&mut self) -> Result<std::vec::Vec::<VLANInfo>, GS1900Error> {
+        self.write_command("show vlan")?;
+
+        let raw = self.fetch_data()?;
+        let data = self.clean_data(raw);
+
+        parse_vlans(&data)
+    }
+
+    pub fn dns_config(&mut self) -> Result<DNSConfig, GS1900Error> {
+        self.write_command("show ip name-server")?;
+        let mut result = DNSConfig { servers: std::vec::Vec::new(), domain: None };
+
+        let raw = self.fetch_data()?;
+        let data = self.clean_data(raw);
+
+        for line in data.split("\n") {
+            let kv: Vec<&str> = line.split(" : ").collect();
+            if kv.len() < 2 {
+                /* some firmware versions just list one server per line */
+                if let Ok(ip) = line.trim().parse::<IPv4Address>() {
+                    result.servers.push(ip);
+                }
+                continue;
+            }
+
+            let key = kv[0].trim();
+            let val = kv[1].trim();
+
+            if key.contains("Server") {
+                if val.is_empty() || val == "0.0.0.0" {
+                    continue;
+                }
+                result.servers.push(val.parse().in_line(line)?);
+            } else if key == "Domain Name" && !val.is_empty() {
+                result.domain = Some(val.to_string());
+            }
+        }
+
+        Ok(result)
+    }
+
+    /// Power-on self-test results (empty if the firmware does not retain them)
+    pub fn post_results(&mut self) -> Result<std::vec::Vec::<PostResult>, GS1900Error> {
+        self.write_command("show post")?;
+        let mut result = std::vec::Vec::<PostResult>::new();
+
+        let raw = match self.fetch_data() {
+            Err(GS1900Error::CommandRejected { .. }) => return Ok(result),
+            x => x?,
+        };
+        let data = self.clean_data(raw);
+
+        for line in data.split("\n") {
+            let kv: Vec<&str> = line.split(" : ").collect();
+            if kv.len() < 2 {
+                continue;
+            }
+
+            let test = kv[0].trim().trim_end_matches('.').trim();
+            let val = kv[1].trim();
+            let (status, detail) = match val.find(' ') {
+                Some(x) => (&val[..x], val[x..].trim()),
+                None => (val, ""),
+            };
+
+            let passed = match status.to_uppercase().as_str() {
+                "PASS" | "PASSED" | "OK" => true,
+                "FAIL" | "FAILED" | "ERROR" => false,
+                _ => continue,
+            };
+
+            result.push(PostResult {
+                test: test.to_string(),
+                passed,
+                detail: detail.trim_start_matches('(').trim_end_matches(')').to_string(),
+            });
+        }
+
+        Ok(result)
+    }
+
+    /// Email alert configuration (disabled if not configured or not supported)
+    pub fn email_alert_config(&mut self) -> Result<EmailAlertConfig, GS1900Error> {
+        self.write_command("show email-alert")?;
+        let mut result = EmailAlertConfig { enabled: false, server: None, sender: None, recipients: std::vec::Vec::new(), severities: std::vec::Vec::new() };
+
+        let raw = match self.fetch_data() {
+            Err(GS1900Error::CommandRejected { .. }) => return Ok(result),
+            x => x?,
+        };
+        let data = self.clean_data(raw);
+
+        for line in data.split("\n") {
+            let kv: Vec<&str> = line.split(" : ").collect();
+            if kv.len() < 2 {
+                continue;
+            }
+
+            let key = kv[0].trim();
+            let val = kv[1].trim();
+            if val.is_empty() {
+                continue;
+            }
+
+            match key {
+                "Status" => result.enabled = val == "Enabled",
+                "Server" | "SMTP Server" => result.server = Some(val.to_string()),
+                "Sender" | "From" => result.sender = Some(val.to_string()),
+                "Recipient" | "To" => result.recipients.push(val.to_string()),
+                _ => {
+                    /* one line per severity, e.g. "Critical : Enabled" */
+                    if let Ok(severity) = key.parse::<LogSeverity>() {
+                        if val == "Enabled" {
+                            result.severities.push(severity);
+                        }
+                    }
+                },
+            }
+        }
+
+        Ok(result)
+    }
+
+    /// Configure the SMTP server and replace the email alert recipients
+    pub fn set_email_alert(&mut self, server: &str, recipients: &[&str]) -> Result<(), GS1900Error> {
+        if server.is_empty() || server.contains(char::is_whitespace) {
+            return Err(std::io::Error::new(std::io::ErrorKind::InvalidInput, "Invalid SMTP server").into());
+        }
+        if recipients.iter().any(|r| !r.contains('@') || r.contains(char::is_whitespace)) {
+            return Err(std::io::Error::new(std::io::ErrorKind::InvalidInput, "Invalid email recipient").into());
+        }
+
+        let mut cmds = vec![format!("email-alert server {}", server), "no email-alert recipient".to_string()];
+        cmds.extend(recipients.iter().map(|r| format!("email-alert recipient {}", r)));
+        self.configure(&cmds)
+    }
+
+    /// IP interfaces of all VLANs
+    ///
+    /// Pure L2 models only report the management interface, which is
+    /// assumed to be in VLAN 1. Its state is unknown in that case.
+    pub fn ip_interfaces(&mut self) -> Result<std::vec::Vec::<IPInterface>, GS1900Error> {
+        self.write_command("show ip interface")?;
+        let mut result = std::vec::Vec::<IPInterface>::new();
+
+        let raw = match self.fetch_data() {
+            Err(GS1900Error::CommandRejected { .. }) => {
+                let info = self.basic_info()?;
+                result.push(IPInterface { vlan: 1, ip: info.ip_address, mask: info.subnet_mask, admin_up: None, link_up: None });
+                return Ok(result);
+            },
+            x => x?,
+        };
+        let data = self.clean_data(raw);
+
+        for e in parse_pipe_table(&data, 4, "Interface") {
+            let vlan = e[0].trim_start_matches(|c: char| c.is_ascii_alphabetic() || c == ' ');
+            let addr: Vec<&str> = e[1].split('/').collect();
+            if addr.len() != 2 {
+                return Err(GS1900Error::parse("Invalid interface address", &e));
+            }
+            let mask = match addr[1].parse::<u8>() {
+                Ok(len) => IPv4Address::netmask(len).in_line(&e)?,
+                Err(_) => addr[1].parse().in_line(&e)?,
+            };
+
+            result.push(IPInterface {
+                vlan: vlan.parse().in_line(&e)?,
+                ip: addr[0].parse().in_line(&e)?,
+                mask,
+                admin_up: Some(e[2].eq_ignore_ascii_case("up")),
+                link_up: Some(e[3].eq_ignore_ascii_case("up")),
+            });
+        }
+
+        Ok(result)
+    }
+
+    /// Configured static routes (empty on models without routing support)
+    pub fn static_routes(&mut self) -> Result<std::vec::Vec::<StaticRoute>, GS1900Error> {
+        self.write_command("show ip route static")?;
+        let mut result = std::vec::Vec::<StaticRoute>::new();
+
+        lazy_static! {
+            static ref RE: Regex = Regex::new(r"^\s*S\*?\s+(\d+\.\d+\.\d+\.\d+)/(\d+)\s+(?:\[(\d+)/(\d+)\]\s+)?via\s+(\d+\.\d+\.\d+\.\d+)").unwrap();
+        }
+
+        let raw = match self.fetch_data() {
+            Err(GS1900Error::CommandRejected { .. }) => return Ok(result),
+            x => x?,
+        };
+        let data = self.clean_data(raw);
+
+        for line in data.split("\n") {
+            for cap in RE.captures_iter(line) {
+                let prefix_len = cap[2].parse().in_line(line)?;
+                if prefix_len > 32 {
+                    return Err(GS1900Error::parse("Invalid prefix length", line));
+                }
+                let metric = match cap.get(4) {
+                    Some(x) => Some(x.as_str().parse().in_line(line)?),
+                    None => None,
+                };
+
+                result.push(StaticRoute {
+                    destination: cap[1].parse().in_line(line)?,
+                    prefix_len,
+                    gateway: cap[5].parse().in_line(line)?,
+                    metric,
+                });
+            }
+        }
+
+        Ok(result)
+    }
+
+    /// Add a static route (use 0.0.0.0/0 for the default route)
+    pub fn add_static_route(&mut self, destination: IPv4Address, prefix_len: u8, gateway: IPv4Address) -> Result<(), GS1900Error> {
+        let mask = IPv4Address::netmask(prefix_len)?;
+        self.configure(&[format!("ip route {} {} {}", destination, mask, gateway)])
+    }
+
+    /// Remove a static route
+    pub fn remove_static_route(&mut self, destination: IPv4Address, prefix_len: u8, gateway: IPv4Address) -> Result<(), GS1900Error> {
+        let mask = IPv4Address::netmask(prefix_len)?;
+        self.configure(&[format!("no ip route {} {} {}", destination, mask, gateway)])
+    }
+
+    /// Ping a host from the switch
+    pub fn ping(&mut self, target: IPv4Address, count: u8) -> Result<PingResult, GS1900Error> {
+        self.ping_progress(target, count, |_| {})
+    }
+
+    /// Ping a host from the switch, passing the output to progress while it arrives
+    pub fn ping_progress<F>(&mut self, target: IPv4Address, count: u8, mut progress: F) -> Result<PingResult, GS1900Error>
+        where F: FnMut(&str) {
+        if count == 0 {
+            return Err(std::io::Error::new(std::io::ErrorKind::InvalidInput, "Invalid ping count").into());
+        }
+
+        self.write_command(format!("ping {} count {}", target, count).as_str())?;
+
+        /*
+         * Replies trickle in once per second and lost echoes may print nothing
+         * until the summary, so allow at least one second per echo.
+         */
+        let timeout = self.config.command_timeout.max(std::time::Duration::from_secs(u64::from(count)));
+        let raw = self.fetch_data_progress(duration_ms(timeout), &mut progress)?;
+        let data = self.clean_data(raw);
+
+        parse_ping(&data)
+    }
+
+    /// Reason of the last reboot (Unknown if the firmware does not record it)
+    pub fn last_reboot(&mut self) -> Result<RebootInfo, GS1900Error> {
+        self.write_command("show reboot-cause")?;
+
+        let raw = match self.fetch_data() {
+            Err(GS1900Error::CommandRejected { .. }) => return Ok(parse_reboot_cause("")),
+            x => x?,
+        };
+        let data = self.clean_data(raw);
+
+        Ok(parse_reboot_cause(&data))
+    }
+
+    /// Replace the configured name servers (an empty list removes all of them)
+    pub fn set_dns_servers(&mut self, servers: &[IPv4Address]) -> Result<(), GS1900Error> {
+        let cmd = if servers.is_empty() {
+            "no ip name-server".to_string()
+        } else {
+            let list: Vec<String> = servers.iter().map(|s| s.to_string()).collect();
+            format!("ip name-server {}", list.join(" "))
+        };
+
+        self.configure(&[cmd])
+    }
+
+    pub fn syslog_config(&mut self) -> Result<SyslogConfig, GS1900Error> {
+        self.write_command("show logging")?;
+        let mut result = SyslogConfig { buffer_enabled: false, console_level: LogSeverity::Warning, remote_servers: std::vec::Vec::new() };
+
+        let raw = self.fetch_data()?;
+        let data = self.clean_data(raw);
+
+        for e in parse_pipe_table(&data, 6, "TARGET") {
+            let enabled = e[1] == "enabled";
+            /* level is either a single threshold or a list of all logged severities */
+            let level: LogSeverity = match e[5].split(",").last() {
+                Some(x) => x.trim().parse().in_line(&e)?,
+                None => continue,
+            };
+
+            match e[0] {
+                "buffered" => result.buffer_enabled = enabled,
+                "console" => result.console_level = level,
+                "host" if enabled => result.remote_servers.push((e[2].parse().in_line(&e)?, level)),
+                _ => {},
+            }
+        }
+
+        Ok(result)
+    }
+
+    /// Over-temperature events recorded in the RAM log buffer
+    pub fn thermal_events(&mut self) -> Result<std::vec::Vec::<ThermalEvent>, GS1900Error> {
+        self.write_command("show logging buffered")?;
+        let mut result = std::vec::Vec::<ThermalEvent>::new();
+
+        lazy_static! {
+            static ref SENSOR: Regex = Regex::new(r"(?i)sensor\s*(\S+?)[,:]?(?:\s|$)").unwrap();
+            static ref TEMP: Regex = Regex::new(r"(-?\d+(?:\.\d+)?)\s*(?:C\b|°C|degrees)").unwrap();
+        }
+
+        let raw = self.fetch_data()?;
+        let data = self.clean_data(raw);
+
+        for line in data.split("\n") {
+            let lower = line.to_lowercase();
+            if !lower.contains("temperature") && !lower.contains("thermal") {
+                continue;
+            }
+
+            result.push(ThermalEvent {
+                timestamp: log_timestamp(line),
+                sensor: SENSOR.captures(line).map(|c| c[1].to_string()).unwrap_or_default(),
+                temperature: TEMP.captures(line).and_then(|c| c[1].parse().ok()),
+                message: line.trim().to_string(),
+            });
+        }
+
+        Ok(result)
+    }
+
+    /// PoE events from the system log (empty if the log buffer does not contain any)
+    pub fn poe_events(&mut self) -> Result<std::vec::Vec::<PoEEvent>, GS1900Error> {
+        self.write_command("show logging buffered")?;
+        let mut result = std::vec::Vec::<PoEEvent>::new();
+
+        lazy_static! {
+            static ref PORT: Regex = Regex::new(r"(?i)(?:port|interface|gi|GigabitEthernet)\s*(\d+)").unwrap();
+        }
+
+        let raw = self.fetch_data()?;
+        let data = self.clean_data(raw);
+
+        for line in data.split("\n") {
+            let lower = line.to_lowercase();
+            if !lower.contains("poe") && !lower.contains("power inline") {
+                continue;
+            }
+
+            /* order matters, "disconnected" contains "connected" */
+            let event = if lower.contains("overload") || lower.contains("over load") || lower.contains("overcurrent") {
+                PoEEventKind::Overload
+            } else if lower.contains("short") {
+                PoEEventKind::Short
+            } else if lower.contains("denied") || lower.contains("insufficient") || lower.contains("budget") {
+                PoEEventKind::PowerDenied
+            } else if lower.contains("disconnect") || lower.contains("removed") || lower.contains("power off") {
+                PoEEventKind::Disconnected
+            } else if lower.contains("connected") || lower.contains("detected") || lower.contains("power on") {
+                PoEEventKind::Connected
+            } else {
+                continue;
+            };
+
+            let port = match PORT.captures(line).and_then(|c| c[1].parse().ok()) {
+                Some(port) => port,
+                None => continue,
+            };
+
+            result.push(PoEEvent {
+                port,
+                timestamp: log_timestamp(line),
+                event,
+            });
+        }
+
+        Ok(result)
+    }
+
+    /// Clear thermal events. The firmware has no dedicated thermal log and
+    /// clearing the RAM log buffer would also drop every other message (e.g.
+    /// the PoE history), so this always fails with Unsupported.
+    pub fn clear_thermal_events(&mut self) -> Result<(), GS1900Error> {
+        Err(GS1900Error::Unsupported { feature: "clearing thermal events".to_string() })
+    }
+
+    /// Add a remote syslog server receiving messages up to the given severity
+    pub fn set_syslog_server(&mut self, server: IPv4Address, severity: LogSeverity) -> Result<(), GS1900Error> {
+        self.configure(&[format!("logging host {} severity {}", server, severity as u8)])
+    }
+
+    pub fn alarms(&mut self) -> Result<std::vec::Vec::<Alarm>, GS1900Error> {
+        self.write_command("show alarm")?;
+
+        let raw = self.fetch_data()?;
+        let data = self.clean_data(raw);
+
+        parse_alarms(&data)
+    }
+
+    /// Acknowledge all alarms, which removes them from the alarm table
+    pub fn clear_alarms(&mut self) -> Result<(), GS1900Error> {
+        self.check_writable()?;
+        self.command("clear alarm")?;
+        Ok(())
+    }
+
+    /// Configured ACL rules (empty if no ACLs are configured)
+    pub fn acl_rules(&mut self) -> Result<std::vec::Vec::<ACLRule>, GS1900Error> {
+        self.write_command("show access-list")?;
+        let mut result = std::vec::Vec::<ACLRule>::new();
+
+        let raw = self.fetch_data()?;
+        let data = self.clean_data(raw);
+
+        for e in parse_pipe_table(&data, 5, "ID") {
+            let rule = ACLRule {
+                id: e[0].parse().in_line(&e)?,
+                action: e[1].parse().in_line(&e)?,
+                match_criteria: ACLMatch::parse(e[2], e[3]),
+                bound_ports: e[4].to_string(),
+            };
+            result.push(rule);
+        }
+
+        Ok(result)
+    }
+
+    /// Summary of the global loop/topology protection settings
+    pub fn protection_config(&mut self) -> Result<ProtectionConfig, GS1900Error> {
+        let stp = self.global_states("show spanning-tree")?;
+        let loopguard = self.global_states("show loop-guard")?;
+        let snooping = self.global_states("show ip dhcp snooping")?;
+
+        Ok(ProtectionConfig {
+            bpdu_guard: stp.get("BPDU Guard").cloned(),
+            root_guard: stp.get("Root Guard").cloned(),
+            loop_guard: loopguard.get("Loop Guard").or_else(|| loopguard.get("Loop Guard Status")).cloned(),
+            dhcp_snooping: snooping.get("DHCP Snooping").or_else(|| snooping.get("DHCP Snooping Status")).cloned(),
+        })
+    }
+
+    /// Time after which ports shut down by loop guard are enabled again (zero for manual recovery)
+    pub fn loop_recovery_interval(&mut self) -> Result<std::time::Duration, GS1900Error> {
+        self.write_command("show loop-guard")?;
+
+        let raw = self.fetch_data()?;
+        let data = self.clean_data(raw);
+
+        for line in data.split("\n") {
+            let kv: Vec<&str> = line.split(" : ").collect();
+            if kv.len() < 2 || !kv[0].contains("Recovery") {
+                continue;
+            }
+
+            let val = kv[1].trim().trim_end_matches("seconds").trim_end_matches("sec").trim();
+            if val == "Disabled" || val == "disabled" {
+                return Ok(std::time::Duration::from_secs(0));
+            }
+            let secs = val.parse().in_line(line)?;
+            return Ok(std::time::Duration::from_secs(secs));
+        }
+
+        Err(GS1900Error::parse("Missing loop guard recovery interval", ""))
+    }
+
+    /// Set the loop guard recovery interval (30-86400 seconds, zero for manual recovery)
+    pub fn set_loop_recovery_interval(&mut self, interval: std::time::Duration) -> Result<(), GS1900Error> {
+        let secs = interval.as_secs();
+        if secs != 0 && !(30..=86400).contains(&secs) {
+            return Err(std::io::Error::new(std::io::ErrorKind::InvalidInput, "Invalid loop guard recovery interval").into());
+        }
+
+        self.configure(&[format!("loop-guard recovery-time {}", secs)])
+    }
+
+    /// Link flap dampening settings of every port (disabled for unconfigured ports)
+    pub fn flap_dampening(&mut self) -> Result<std::vec::Vec::<FlapDampening>, GS1900Error> {
+        let ports = self.interface_status_int("all")?;
+
+        self.write_command("show link-flap interfaces all")?;
+        let raw = self.fetch_data()?;
+        let data = self.clean_data(raw);
+
+        let mut configured = std::collections::HashMap::new();
+        for e in parse_pipe_table(&data, 5, "Port") {
+            let num = |x: &str| x.parse().in_line(&e);
+            let port: u8 = e[0].trim_start_matches(|c: char| c.is_ascii_alphabetic()).parse().in_line(&e)?;
+            configured.insert(port, FlapDampening {
+                port,
+                enabled: e[1] == "Enabled" || e[1] == "enabled",
+                threshold: num(e[2])?,
+                window_seconds: num(e[3])?,
+                action: e[4].parse().in_line(&e)?,
+            });
+        }
+
+        Ok(ports.iter().map(|p| match configured.get(&p.port) {
+            Some(x) => *x,
+            None => FlapDampening { port: p.port, enabled: false, threshold: 0, window_seconds: 0, action: FlapAction::Shutdown },
+        }).collect())
+    }
+
+    /// Configure link flap dampening of a port (threshold, window in seconds, action) or disable it
+    pub fn set_flap_dampening(&mut self, port: u8, config: Option<(u32, u32, FlapAction)>) -> Result<(), GS1900Error> {
+        let cmd = match config {
+            Some((threshold, window, action)) => {
+                if threshold == 0 || window == 0 {
+                    return Err(std::io::Error::new(std::io::ErrorKind::InvalidInput, "Invalid link flap threshold").into());
+                }
+                format!("link-flap threshold {} window {} action {}", threshold, window, action)
+            },
+            None => "no link-flap".to_string(),
+        };
+
+        self.configure_interface(port, &[cmd])
+    }
+
+    /// DHCP snooping settings of every port (empty if DHCP snooping is disabled)
+    pub fn dhcp_snooping_ports(&mut self) -> Result<std::vec::Vec::<DHCPSnoopPort>, GS1900Error> {
+        let mut result = std::vec::Vec::<DHCPSnoopPort>::new();
+
+        let snooping = self.global_states("show ip dhcp snooping")?;
+        if snooping.get("DHCP Snooping").or_else(|| snooping.get("DHCP Snooping Status")) != Some(&true) {
+            return Ok(result);
+        }
+
+        self.write_command("show ip dhcp snooping interfaces all")?;
+
+        let raw = self.fetch_data()?;
+        let data = self.clean_data(raw);
+
+        for e in parse_pipe_table(&data, 3, "Port") {
+            let port = e[0].trim_start_matches(|c: char| c.is_ascii_alphabetic());
+            let rate_limit = match e[2] {
+                "Unlimited" | "unlimited" | "None" | "none" | "-" => None,
+                x => Some(x.parse().in_line(&e)?),
+            };
+
+            result.push(DHCPSnoopPort {
+                port: port.parse().in_line(&e)?,
+                trusted: e[1] == "Trusted" || e[1] == "trusted" || e[1] == "Yes",
+                rate_limit,
+            });
+        }
+
+        Ok(result)
+    }
+
+    /// DHCP snooping and dynamic ARP inspection drop counters of every port
+    pub fn security_violations(&mut self) -> Result<SecurityViolations, GS1900Error> {
+        let mut result = SecurityViolations {
+            dhcp_snooping: std::vec::Vec::new(),
+            arp_inspection: std::vec::Vec::new(),
+        };
+
+        let snooping = self.global_states("show ip dhcp snooping")?;
+        if snooping.get("DHCP Snooping").or_else(|| snooping.get("DHCP Snooping Status")) == Some(&true) {
+            result.dhcp_snooping = self.drop_counters("show ip dhcp snooping interfaces all statistics")?;
+        }
+
+        let inspection = self.global_states("show ip arp inspection")?;
+        if inspection.get("Dynamic ARP Inspection").or_else(|| inspection.get("ARP Inspection")).or_else(|| inspection.get("ARP Inspection Status")) == Some(&true) {
+            result.arp_inspection = self.drop_counters("show ip arp inspection statistics")?;
+        }
+
+        Ok(result)
+    }
+
+    /// Per port "Drop" column of a statistics table (empty if the command is not supported)
+    fn drop_counters(&mut self, cmd: &str) -> Result<std::vec::Vec<PortDrops>, GS1900Error> {
+        self.write_command(cmd)?;
+        let mut result = std::vec::Vec::new();
+
+        let raw = match self.fetch_data() {
+            Err(GS1900Error::CommandRejected { .. }) => return Ok(result),
+            x => x?,
+        };
+        let data = self.clean_data(raw);
+
+        let mut column = None;
+        for e in parse_pipe_table(&data, 2, "") {
+            if e[0] == "Port" {
+                column = e.iter().position(|x| x.contains("Drop") || x.contains("drop"));
+                continue;
+            }
+            let col = match column {
+                Some(col) if col < e.len() && !e[0].starts_with('-') => col,
+                _ => continue,
+            };
+
+            let port = e[0].trim_start_matches(|c: char| c.is_ascii_alphabetic());
+            result.push(PortDrops {
+                port: port.parse().in_line(&e)?,
+                dropped: e[col].parse().in_line(&e)?,
+            });
+        }
+
+        Ok(result)
+    }
+
+    /// Mark a port as trusted (or untrusted) for DHCP snooping
+    pub fn set_dhcp_snoop_trust(&mut self, port: u8, trusted: bool) -> Result<(), GS1900Error> {
+        let cmd = if trusted { "ip dhcp snooping trust" } else { "no ip dhcp snooping trust" };
+        self.configure_interface(port, &[cmd.to_string()])
+    }
+
+    /// Enable state of the management services
+    pub fn services(&mut self) -> Result<ServiceStatus, GS1900Error> {
+        let states = self.global_states("show ip service")?;
+
+        Ok(ServiceStatus {
+            ssh: states.get("SSH").cloned(),
+            telnet: states.get("Telnet").cloned(),
+            http: states.get("HTTP").cloned(),
+            https: states.get("HTTPS").cloned(),
+            snmp: states.get("SNMP").cloned(),
+        })
+    }
+
+    /// Enable or disable the SSH server
+    ///
+    /// Disabling SSH locks out this crate: the current session keeps working,
+    /// but neither reconnect() nor new() will be able to log in again.
+    pub fn set_ssh_enabled(&mut self, enabled: bool) -> Result<(), GS1900Error> {
+        self.set_service("ip ssh", enabled)
+    }
+
+    /// Enable or disable the Telnet server
+    pub fn set_telnet_enabled(&mut self, enabled: bool) -> Result<(), GS1900Error> {
+        self.set_service("ip telnet", enabled)
+    }
+
+    /// Enable or disable the HTTP server
+    ///
+    /// The web interface is used by the control_* functions (web feature).
+    pub fn set_http_enabled(&mut self, enabled: bool) -> Result<(), GS1900Error> {
+        self.set_service("ip http server", enabled)
+    }
+
+    /// Enable or disable the HTTPS server
+    pub fn set_https_enabled(&mut self, enabled: bool) -> Result<(), GS1900Error> {
+        self.set_service("ip http secure-server", enabled)
+    }
+
+    /// Enable or disable the SNMP agent
+    pub fn set_snmp_enabled(&mut self, enabled: bool) -> Result<(), GS1900Error> {
+        self.set_service("snmp", enabled)
+    }
+
+    fn set_service(&mut self, cmd: &str, enabled: bool) -> Result<(), GS1900Error> {
+        let cmd = if enabled { cmd.to_string() } else { format!("no {}", cmd) };
+        self.configure(&[cmd])
+    }
+
+    /// Enabled/Disabled lines of a show command (empty if the command is rejected)
+    fn global_states(&mut self, cmd: &str) -> Result<std::collections::HashMap<String, bool>, GS1900Error> {
+        self.write_command(cmd)?;
+        let mut result = std::collections::HashMap::new();
+
+        let raw = match self.fetch_data() {
+            Err(GS1900Error::CommandRejected { .. }) => return Ok(result),
+            x => x?,
+        };
+        let data = self.clean_data(raw);
+
+        for line in data.split("\n") {
+            let kv: Vec<&str> = line.split(" : ").collect();
+            if kv.len() < 2 {
+                continue;
+            }
+
+            let state = match kv[1].trim() {
+                "Enabled" | "enabled" => true,
+                "Disabled" | "disabled" => false,
+                _ => continue,
+            };
+            result.insert(kv[0].trim().to_string(), state);
+        }
+
+        Ok(result)
+    }
+
+    /// Current port LED mode
+    pub fn led_mode(&mut self) -> Result<LEDMode, GS1900Error> {
+        self.write_command("show led")?;
+
+        let raw = match self.fetch_data() {
+            Err(GS1900Error::CommandRejected { .. }) => {
+                return Err(GS1900Error::Unsupported { feature: "LED mode".to_string() });
+            },
+            x => x?,
+        };
+        let data = self.clean_data(raw);
+
+        for line in data.split("\n") {
+            let kv: Vec<&str> = line.split(" : ").collect();
+            if kv.len() >= 2 && kv[0].trim() == "LED Mode" {
+                return kv[1].trim().parse().in_line(line);
+            }
+        }
+
+        Err(GS1900Error::Unsupported { feature: "LED mode".to_string() })
+    }
+
+    /// Change the port LED mode
+    pub fn set_led_mode(&mut self, mode: LEDMode) -> Result<(), GS1900Error> {
+        match self.configure(&[format!("led mode {}", mode)]) {
+            Err(GS1900Error::CommandRejected { .. }) => {
+                return Err(GS1900Error::Unsupported { feature: "LED mode".to_string() });
+            },
+            x => x?,
+        }
+
+        if self.led_mode()? != mode {
+            return Err(std::io::Error::other("LED mode change was not applied").into());
+        }
+        Ok(())
+    }
+
+    /// Supported and enabled port mirroring sessions
+    pub fn mirror_capacity(&mut self) -> Result<MirrorCapacity, GS1900Error> {
+        self.write_command("show port-monitor")?;
+        let mut result = MirrorCapacity { max_sessions: MIRROR_SESSIONS, in_use: 0 };
+
+        let raw = self.fetch_data()?;
+        let data = self.clean_data(raw);
+
+        let mut listed = 0;
+        for line in data.split("\n") {
+            let kv: Vec<&str> = line.split(" : ").collect();
+            if kv.len() < 2 {
+                continue;
+            }
+
+            match kv[0].trim() {
+                "Session" | "Session ID" => listed += 1,
+                "Status" | "Session Status" if kv[1].trim() == "Enabled" => result.in_use += 1,
+                _ => {},
+            }
+        }
+
+        if listed == 0 {
+            return Err(GS1900Error::parse("Missing mirror session", ""));
+        }
+        /* firmware versions listing every session slot may support more */
+        result.max_sessions = result.max_sessions.max(listed);
+
+        Ok(result)
+    }
+
+    /// Blink the LED of a port to identify it
+    pub fn locate_port(&mut self, port: u8, duration: std::time::Duration) -> Result<(), GS1900Error> {
+        self.locate(format!("locate interfaces {}", port).as_str(), duration)
+    }
+
+    /// Blink the LEDs of the switch to identify it
+    pub fn locate_switch(&mut self, duration: std::time::Duration) -> Result<(), GS1900Error> {
+        self.locate("locate system", duration)
+    }
+
+    fn locate(&mut self, cmd: &str, duration: std::time::Duration) -> Result<(), GS1900Error> {
+        let secs = duration.as_secs();
+        if secs == 0 {
+            return Err(std::io::Error::new(std::io::ErrorKind::InvalidInput, "Invalid locate duration").into());
+        }
+
+        match self.command(format!("{} time {}", cmd, secs).as_str()) {
+            Err(GS1900Error::CommandRejected { .. }) => {
+                Err(GS1900Error::Unsupported { feature: "locate".to_string() })
+            },
+            x => x.map(|_| ()),
+        }
+    }
+
+    /// Default CoS value of every port
+    pub fn port_priority(&mut self) -> Result<std::vec::Vec::<PortPriority>, GS1900Error> {
+        self.write_command("show qos interfaces all")?;
+        let mut result = std::vec::Vec::<PortPriority>::new();
+
+        let raw = self.fetch_data()?;
+        let data = self.clean_data(raw);
+
+        for e in parse_pipe_table(&data, 2, "Port") {
+            let port = e[0].trim_start_matches(|c: char| c.is_ascii_alphabetic());
+            result.push(PortPriority {
+                port: port.parse().in_line(&e)?,
+                default_cos: e[1].parse().in_line(&e)?,
+            });
+        }
+
+        Ok(result)
+    }
+
+    /// Set the CoS value assigned to untagged frames received on a port
+    pub fn set_port_priority(&mut self, port: u8, cos: u8) -> Result<(), GS1900Error> {
+        if cos > 7 {
+            return Err(std::io::Error::new(std::io::ErrorKind::InvalidInput, "Invalid CoS value").into());
+        }
+
+        self.configure_interface(port, &[format!("qos cos {}", cos)])
+    }
+
+    /// Spanning tree path cost and priority of every port
+    pub fn stp_port_config(&mut self) -> Result<std::vec::Vec::<STPPortConfig>, GS1900Error> {
+        self.write_command("show spanning-tree interfaces all")?;
+        let mut result = std::vec::Vec::<STPPortConfig>::new();
+
+        let raw = self.fetch_data()?;
+        let data = self.clean_data(raw);
+
+        for e in parse_pipe_table(&data, 3, "Port") {
+            let port = e[0].trim_start_matches(|c: char| c.is_ascii_alphabetic());
+            /* priority may be reported as priority.port-number */
+            let prio = e[2].split('.').next().unwrap_or("");
+            result.push(STPPortConfig {
+                port: port.parse().in_line(&e)?,
+                cost: e[1].parse().in_line(&e)?,
+                priority: prio.parse().in_line(&e)?,
+            });
+        }
+
+        Ok(result)
+    }
+
+    /// Spanning tree BPDU counters of every port (empty if not supported by the firmware)
+    pub fn stp_port_stats(&mut self) -> Result<std::vec::Vec::<STPPortStats>, GS1900Error> {
+        self.write_command("show spanning-tree interfaces all statistics")?;
+        let mut result = std::vec::Vec::<STPPortStats>::new();
+
+        let raw = match self.fetch_data() {
+            Err(GS1900Error::CommandRejected { .. }) => return Ok(result),
+            x => x?,
+        };
+        let data = self.clean_data(raw);
+
+        for e in parse_pipe_table(&data, 5, "Port") {
+            let port = e[0].trim_start_matches(|c: char| c.is_ascii_alphabetic());
+            let num = |x: &str| x.parse().in_line(&e);
+            result.push(STPPortStats {
+                port: port.parse().in_line(&e)?,
+                bpdu_tx: num(e[1])?,
+                bpdu_rx: num(e[2])?,
+                tcn_tx: num(e[3])?,
+                tcn_rx: num(e[4])?,
+            });
+        }
+
+        Ok(result)
+    }
+
+    /// Set the spanning tree path cost of a port (1-200000000)
+    pub fn set_stp_port_cost(&mut self, port: u8, cost: u32) -> Result<(), GS1900Error> {
+        if !(1..=200000000).contains(&cost) {
+            return Err(std::io::Error::new(std::io::ErrorKind::InvalidInput, "Invalid STP path cost").into());
+        }
+
+        self.configure_interface(port, &[format!("spanning-tree cost {}", cost)])
+    }
+
+    /// Set the spanning tree priority of a port (0-240 in steps of 16)
+    pub fn set_stp_port_priority(&mut self, port: u8, priority: u8) -> Result<(), GS1900Error> {
+        if priority > 240 || priority & 0x0f != 0 {
+            return Err(std::io::Error::new(std::io::ErrorKind::InvalidInput, "Invalid STP port priority").into());
+        }
+
+        self.configure_interface(port, &[format!("spanning-tree port-priority {}", priority)])
+    }
+
+    /// Configured spanning tree mode
+    pub fn stp_mode(&mut self) -> Result<STPMode, GS1900Error> {
+        self.write_command("show spanning-tree")?;
+
+        let raw = self.fetch_data()?;
+        let data = self.clean_data(raw);
+
+        let mut enabled = None;
+        let mut mode = None;
+        for line in data.split("\n") {
+            let line = line.trim();
+
+            /* "Spanning tree enabled mode RSTP" or "Spanning tree disabled" */
+            if line.to_lowercase().starts_with("spanning tree ") && !line.contains(" : ") {
+                let words: Vec<&str> = line.split_whitespace().collect();
+                enabled = Some(words.get(2) == Some(&"enabled"));
+                if words.len() >= 5 && words[3] == "mode" {
+                    mode = Some(words[4].parse().in_line(line)?);
+                }
+                continue;
+            }
+
+            let kv: Vec<&str> = line.split(" : ").collect();
+            if kv.len() < 2 {
+                continue;
+            }
+            match kv[0].trim() {
+                "Spanning Tree" | "Spanning Tree Status" | "State" => enabled = Some(kv[1].trim().eq_ignore_ascii_case("enabled")),
+                "Mode" | "Spanning Tree Mode" | "Force Version" => mode = Some(kv[1].trim().parse().in_line(line)?),
+                _ => {},
+            }
+        }
+
+        match (enabled, mode) {
+            (Some(false), _) => Ok(STPMode::Disabled),
+            (_, Some(m)) => Ok(m),
+            _ => Err(GS1900Error::parse("Missing spanning tree mode", "")),
+        }
+    }
+
+    /// Change the spanning tree mode. This restarts spanning tree on every port,
+    /// so expect a short traffic interruption (and possibly loops when disabling it).
+    pub fn set_stp_mode(&mut self, mode: STPMode) -> Result<(), GS1900Error> {
+        match mode {
+            STPMode::Disabled => self.configure(&["no spanning-tree".to_string()])?,
+            _ => self.configure(&["spanning-tree".to_string(), format!("spanning-tree mode {}", mode)])?,
+        }
+
+        if self.stp_mode()? != mode {
+            return Err(std::io::Error::other("spanning tree mode change was not applied").into());
+        }
+        Ok(())
+    }
+
+    /// Management access restrictions (empty if management access is unrestricted)
+    pub fn management_acl(&mut self) -> Result<std::vec::Vec::<ManagementACL>, GS1900Error> {
+        self.write_command("show management-access")?;
+        let mut result = std::vec::Vec::<ManagementACL>::new();
+
+        let raw = self.fetch_data()?;
+        let data = self.clean_data(raw);
+
+        for e in parse_pipe_table(&data, 3, "Service") {
+            result.push(ManagementACL {
+                service: e[0].parse().in_line(&e)?,
+                subnet: e[1].parse().in_line(&e)?,
+                mask: e[2].parse().in_line(&e)?,
+            });
+        }
+
+        Ok(result)
+    }
+
+    /// Optical link counters of a fiber port
+    pub fn fiber_counters(&mut self, port: u8) -> Result<FiberCounters, GS1900Error> {
+        /* an SFP cage may be empty or a combo port linked on copper */
+        if !self.sfp_ports()?.contains(&port) {
+            return Err(std::io::Error::new(std::io::ErrorKind::InvalidInput, format!("Port {} has no SFP cage", port)).into());
+        }
+
+        self.write_command(format!("show fiber-transceiver interfaces {} counters", port).as_str())?;
+        let mut result = FiberCounters { port, ..Default::default() };
+
+        let raw = match self.fetch_data() {
+            Err(GS1900Error::CommandRejected { .. }) => {
+                return Err(GS1900Error::Unsupported { feature: "fiber counters".to_string() });
+            },
+            x => x?,
+        };
+        let data = self.clean_data(raw);
+
+        for line in data.split("\n") {
+            let kv: Vec<&str> = line.split(" : ").collect();
+            if kv.len() < 2 {
+                continue;
+            }
+
+            let val = kv[1].trim().parse().in_line(line);
+            match kv[0].trim() {
+                "RX Errors" => result.rx_errors = val?,
+                "TX Errors" => result.tx_errors = val?,
+                "Link Down" | "Link Drops" => result.link_drops = val?,
+                _ => {},
+            }
+        }
+
+        Ok(result)
+    }
+
+    /// GVRP state (disabled if GVRP is off or not supported)
+    pub fn gvrp_config(&mut self) -> Result<GVRPConfig, GS1900Error> {
+        self.write_command("show gvrp")?;
+        let mut result = GVRPConfig { enabled: false, ports: std::collections::HashMap::new() };
+
+        let raw = match self.fetch_data() {
+            Err(GS1900Error::CommandRejected { .. }) => return Ok(result),
+            x => x?,
+        };
+        let data = self.clean_data(raw);
+
+        for line in data.split("\n") {
+            let kv: Vec<&str> = line.split(" : ").collect();
+            if kv.len() >= 2 && kv[0].trim() == "GVRP Status" {
+                result.enabled = kv[1].trim() == "Enabled";
             }
-            if e[0].trim() == "VID" {
+        }
+
+        if !result.enabled {
+            return Ok(result);
+        }
+
+        for e in parse_pipe_table(&data, 2, "Port") {
+            let port = e[0].trim_start_matches(|c: char| c.is_ascii_alphabetic());
+            let port = port.parse().in_line(&e)?;
+            result.ports.insert(port, e[1].parse().in_line(&e)?);
+        }
+
+        Ok(result)
+    }
+
+    /// Configured time zone and daylight saving time rule (UTC without DST by default)
+    pub fn timezone(&mut self) -> Result<TimezoneConfig, GS1900Error> {
+        self.write_command("show running-config")?;
+        let mut result = TimezoneConfig { name: "UTC".to_string(), offset_minutes: 0, dst: None };
+
+        let raw = self.fetch_data()?;
+        let data = self.clean_data(raw);
+
+        for line in data.split("\n") {
+            let tokens: Vec<&str> = line.split_whitespace().collect();
+            if tokens.len() < 4 || tokens[0] != "clock" {
                 continue;
             }
 
-            let mac = MacEntry {
-                vlan_id: match e[0].trim().parse() {
-                    Ok(x) => x,
-                    Err(_fail) => {
-                        return Err(std::io::Error::new(std::io::ErrorKind::Other, "Received invalid data"));
-                    },
-                },
-                mac_address: e[1].trim().to_string().parse()?,
-                entry_type: e[2].trim().to_string().parse()?,
-                ports: e[3].trim().to_string(),
-            };
+            match tokens[1] {
+                /* clock timezone <name> <hours> [<minutes>] */
+                "timezone" => {
+                    let hours: i16 = tokens[3].parse().in_line(line)?;
+                    let minutes: i16 = match tokens.get(4) {
+                        Some(x) => x.parse().in_line(line)?,
+                        None => 0,
+                    };
+                    result.name = tokens[2].to_string();
+                    result.offset_minutes = if hours < 0 || tokens[3].starts_with('-') { hours * 60 - minutes } else { hours * 60 + minutes };
+                },
+                /* clock summer-time <name> recurring (usa | eu | <start> <end> [<offset>]) */
+                "summer-time" if tokens[3] == "recurring" => {
+                    let rule = match &tokens[4..] {
+                        ["usa", ..] => DSTRule {
+                            start: DSTRecurrence::new(2, "Sun", "Mar", (2, 0)),
+                            end: DSTRecurrence::new(1, "Sun", "Nov", (2, 0)),
+                            offset_minutes: 60,
+                        },
+                        ["eu", ..] => DSTRule {
+                            start: DSTRecurrence::new(5, "Sun", "Mar", (1, 0)),
+                            end: DSTRecurrence::new(5, "Sun", "Oct", (1, 0)),
+                            offset_minutes: 60,
+                        },
+                        x => DSTRule {
+                            start: DSTRecurrence::parse(x).in_line(line)?,
+                            end: DSTRecurrence::parse(&x[4..]).in_line(line)?,
+                            offset_minutes: match x.get(8) {
+                                Some(o) => o.parse().in_line(line)?,
+                                None => 60,
+                            },
+                        },
+                    };
+                    result.dst = Some(rule);
+                },
+                _ => {},
+            }
+        }
+
+        Ok(result)
+    }
+
+    /// Change the UTC offset of the configured time zone
+    pub fn set_timezone(&mut self, offset_minutes: i16) -> Result<(), GS1900Error> {
+        if !(-12 * 60..=14 * 60).contains(&offset_minutes) {
+            return Err(std::io::Error::new(std::io::ErrorKind::InvalidInput, "Invalid time zone offset").into());
+        }
+
+        let name = self.timezone()?.name;
+        let sign = if offset_minutes < 0 { "-" } else { "" };
+        let abs = offset_minutes.abs();
+        self.configure(&[format!("clock timezone {} {}{} {}", name, sign, abs / 60, abs % 60)])
+    }
+
+    /// Run multiple commands and return the cleaned output of each one.
+    /// Every command is sent once the previous response has been read up
+    /// to the prompt, so a slow or rejected command cannot mix up the
+    /// responses. A rejected command does not stop the batch; the first
+    /// rejection is returned after all commands have been run. On a read-only
+    /// connection only "show" commands are accepted.
+    pub fn batch(&mut self, commands: &[&str]) -> Result<std::vec::Vec<String>, GS1900Error> {
+        /* validate everything before sending the first command */
+        for cmd in commands {
+            check_command(cmd)?;
+            if !is_show_command(cmd) {
+                self.check_writable()?;
+            }
+        }
+
+        let mut result = std::vec::Vec::new();
+        let mut rejected = None;
+        for cmd in commands {
+            self.write_command(cmd)?;
+            match self.fetch_data() {
+                Ok(raw) => result.push(self.clean_data(raw)),
+                /* the response has been read completely, so the session is still in sync */
+                Err(e @ GS1900Error::CommandRejected { .. }) => {
+                    result.push(self.last_output.clone());
+                    rejected.get_or_insert(e);
+                },
+                Err(e) => return Err(e),
+            }
+        }
+
+        match rejected {
+            Some(e) => Err(e),
+            None => Ok(result),
+        }
+    }
+
+    /// Run commands in configuration mode and return to privileged mode.
+    /// Every response is checked before the next command is sent, so the
+    /// commands after a rejected one are not run (in the wrong configuration
+    /// context). Commands before the rejected one stay applied.
+    fn configure(&mut self, commands: &[String]) -> Result<(), GS1900Error> {
+        self.check_writable()?;
+        if !self.is_privileged() {
+            return Err(GS1900Error::NotPrivileged);
+        }
+
+        self.write_command("configure")?;
+        let (mut output, _) = self.fetch_mode_prompt()?;
+        if let Err(e) = check_rejected(&output) {
+            self.clean_data(output);
+            return Err(e);
+        }
+
+        let mut rejected = None;
+        for cmd in commands {
+            self.write_command(cmd)?;
+            let (data, _) = self.fetch_mode_prompt()?;
+            output += &data;
+            if let Err(GS1900Error::CommandRejected { message }) = check_rejected(&data) {
+                rejected = Some(GS1900Error::CommandRejected { message: format!("'{}': {}", cmd, message) });
+                break;
+            }
+        }
+
+        /* the prompt changes with the hostname */
+        self.write_command("end")?;
+        let (data, prompt) = self.fetch_mode_prompt()?;
+        output += &data;
+        self.prompt = prompt;
+        self.clean_data(output);
+
+        match rejected {
+            Some(e) => Err(e),
+            None => Ok(()),
+        }
+    }
+
+    /// Run commands in the interface configuration of a port, after checking
+    /// that the port exists (otherwise they would run in global configuration)
+    fn configure_interface(&mut self, port: u8, commands: &[String]) -> Result<(), GS1900Error> {
+        self.check_writable()?;
+        self.check_port(port)?;
+
+        let mut all = vec![format!("interface {}", port)];
+        all.extend_from_slice(commands);
+        all.push("exit".to_string());
+        self.configure(&all)
+    }
+
+    /// Read the response to a configuration command, see read_mode_prompt
+    fn fetch_mode_prompt(&mut self) -> Result<(String, String), GS1900Error> {
+        let mut shell = SshShell { session: &self.session, channel: &mut self.channel };
+        read_mode_prompt(&mut shell, &self.last_command, self.config.command_timeout)
+    }
+
+    /// Fail if the connection is read-only
+    fn check_writable(&self) -> Result<(), GS1900Error> {
+        if self.config.read_only {
+            return Err(GS1900Error::ReadOnly);
+        }
+        Ok(())
+    }
+
+    /// Run a command, answer a "(y/n)" confirmation prompt with yes and return
+    /// the cleaned output
+    fn command_confirmed(&mut self, cmd: &str) -> Result<String, GS1900Error> {
+        self.write_command(cmd)?;
+        self.session.set_timeout(duration_ms(self.config.command_timeout));
+
+        let mut data = String::new();
+        loop {
+            let mut buffer = [0; 100];
+            let len = match self.channel.read(&mut buffer) {
+                Ok(x) => x,
+                Err(e) if e.kind() == std::io::ErrorKind::TimedOut => {
+                    return Err(GS1900Error::Timeout { operation: format!("output of '{}' ({} bytes received)", cmd, data.len()) });
+                },
+                Err(e) => return Err(e.into()),
+            };
+            if len == 0 && self.channel.eof() {
+                return Err(self.truncated(&data));
+            }
+            data += &String::from_utf8_lossy(&buffer[0..len]);
+
+            if data.to_lowercase().contains("(y/n)") {
+                self.write_line("y")?;
+                let raw = self.fetch_data()?;
+                return Ok(self.clean_data(raw));
+            }
+
+            let last = data.rsplit('\n').next().unwrap_or("");
+            if self.is_prompt(last) {
+                check_rejected(&data)?;
+                return Ok(self.clean_data(data));
+            }
+        }
+    }
+
+    /// Run a command and return its cleaned output
+    fn command(&mut self, cmd: &str) -> Result<String, GS1900Error> {
+        self.write_command(cmd)?;
+
+        let raw = self.fetch_data()?;
+        Ok(self.clean_data(raw))
+    }
+
+    /// Send a command to the switch
+    fn write_command(&mut self, cmd: &str) -> Result<(), GS1900Error> {
+        debug!("command: {}", cmd);
+        self.write_line(cmd)?;
+        self.last_command = cmd.to_string();
+        Ok(())
+    }
+
+    /// Send a line to the switch without logging it. Control characters would
+    /// allow smuggling additional commands into the session, so they are rejected.
+    fn write_line(&mut self, line: &str) -> Result<(), GS1900Error> {
+        check_command(line)?;
+        self.channel.write_all(format!("{}\n", line).as_bytes())?;
+        Ok(())
+    }
+
+    /// Save the running configuration, so that it survives a reboot. This also
+    /// persists changes done via the web interface. The GS1900 CLI accepts the
+    /// Cisco style "copy running-config startup-config" ("write memory" is not
+    /// available) and requires privileged mode.
+    pub fn save_config(&mut self) -> Result<(), GS1900Error> {
+        self.check_writable()?;
+        if !self.is_privileged() {
+            return Err(GS1900Error::NotPrivileged);
+        }
+
+        self.write_command("copy running-config startup-config")?;
+
+        /* writing the flash takes a few seconds */
+        let raw = self.fetch_data_timeout(10000)?;
+        let data = self.clean_data(raw);
+
+        let lower = data.to_lowercase();
+        if lower.contains("fail") || lower.contains("error") {
+            return Err(GS1900Error::CommandRejected { message: data.trim().to_string() });
+        }
+        Ok(())
+    }
+
+    /// Reboot the switch, optionally saving the running configuration first.
+    /// The SSH session is closed by the switch, so the GS1900 handle is unusable
+    /// afterwards and should be dropped (or reconnected once the switch is back).
+    pub fn reboot(&mut self, save_first: bool) -> Result<(), GS1900Error> {
+        self.check_writable()?;
+        if !self.is_privileged() {
+            return Err(GS1900Error::NotPrivileged);
+        }
+        if save_first {
+            self.save_config()?;
+        }
+
+        self.write_command("reboot")?;
+        self.session.set_timeout(duration_ms(self.config.command_timeout));
+
+        let mut data = String::new();
+        let mut confirmed = false;
+        loop {
+            let mut buffer = [0; 100];
+            let len = match self.channel.read(&mut buffer) {
+                Ok(x) => x,
+                /* the session drops (or stalls) while the switch goes down */
+                Err(_) if confirmed => return Ok(()),
+                Err(e) if e.kind() == std::io::ErrorKind::TimedOut => {
+                    check_rejected(&data)?;
+                    return Err(GS1900Error::Timeout { operation: "reboot confirmation".to_string() });
+                },
+                Err(e) => return Err(e.into()),
+            };
+
+            if len == 0 && self.channel.eof() {
+                return Ok(());
+            }
+            data += &String::from_utf8_lossy(&buffer[0..len]);
+
+            /* "Are you sure? (y/n)" */
+            if !confirmed && data.to_lowercase().contains("(y/n)") {
+                self.write_line("y")?;
+                confirmed = true;
+            }
+        }
+    }
+
+    pub fn nop(&mut self) -> Result<(), GS1900Error> {
+        self.write_command("")?;
+        self.fetch_data()?;
+        Ok(())
+    }
+
+    #[cfg(feature = "web")]
+    fn zyxel_password(password: &str) -> String {
+        let alphabetstr = "ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789";
+        let alphabet:Vec<char> = alphabetstr.chars().collect();
+        let pwchars:Vec<char> = password.chars().collect();
+        let mut result = String::new();
+        let mut i: i32 = password.len() as i32;
+        i -= 1;
+
+        for x in 0..320 {
+            if x % 7 == 6 && i >= 0 {
+                result += format!("{}", pwchars[i as usize]).as_str();
+                i-=1;
+            } else if x == 122 {
+                if password.len() < 10 {
+                    result += "0"
+                } else {
+                    let c = format!("{}", password.len()/10).chars().next().unwrap();
+                    result += format!("{}", c).as_str()
+                }
+            } else if x == 288 {
+                result += format!("{}", password.len()%10).as_str()
+            } else {
+                let rnd = random_integer::random_u8(0, (alphabet.len() as u8)-1);
+                result += format!("{}", alphabet[rnd as usize]).as_str()
+            }
+        }
+
+        result
+    }
+
+    #[cfg(feature = "web")]
+    fn http_login(&mut self) -> Result<(reqwest::blocking::Client, String), GS1900Error> {
+        let client = reqwest::blocking::Client::builder()
+            .connect_timeout(self.config.http_connect_timeout)
+            .timeout(self.config.http_timeout)
+            .build()
+            .map_err(|e| std::io::Error::other(format!("Failed to setup HTTP client: {}", e)))?;
+        let user = &self.config.username;
+        let pass = match self.config.password {
+            Some(ref password) => GS1900::zyxel_password(password),
+            None => return Err(GS1900Error::PasswordRequired),
+        };
+        let dummy = match SystemTime::now().duration_since(SystemTime::UNIX_EPOCH) {
+            Ok(n) => format!("{}000", n.as_secs()),
+            Err(_) => "1000000000000".to_string(),
+        };
+        let url = format!("http://{}/cgi-bin/dispatcher.cgi", self.address);
+
+        debug!("HTTP login to {} as {} (password: ***)", url, user);
+        let authparams = [("login", "1"), ("username", user.as_str()), ("password", pass.as_str()), ("dummy", dummy.as_str())];
+        client.get(url.as_str()).query(&authparams).send().map_err(|e| std::io::Error::other(format!("Failed to login: {}", e)))?;
+
+        /* Yes, GS1900 series is very crappy: login is processed asynchronously */
+        let deadline = std::time::Instant::now() + self.config.http_timeout;
+        let checkparams = [("login_chk", "1"), ("dummy", dummy.as_str())];
+        loop {
+            let response = client.get(url.as_str()).query(&checkparams).send().map_err(|e| std::io::Error::other(format!("Failed to check login: {}", e)))?;
+            let data = response.text().map_err(|e| std::io::Error::other(format!("Failed to decode check login data: {}", e)))?;
+
+            if data == "\nOK\n" {
+                break;
+            }
+
+            if std::time::Instant::now() >= deadline {
+                return Err(std::io::Error::other("HTTP Login failed!").into());
+            }
+
+            std::thread::sleep(std::time::Duration::from_millis(100));
+        }
+
+        let ssidparams = [("cmd", "1")];
+        let response = client.get(url.as_str()).query(&ssidparams).send().map_err(|e| std::io::Error::other(format!("Failed to get session: {}", e)))?;
+        let data = response.text().map_err(|e| std::io::Error::other(format!("Failed to decode get session data: {}", e)))?;
+
+        lazy_static! {
+            static ref RE: Regex = Regex::new(r"setCookie\(.XSSID., .(.*?).\);").unwrap();
+        }
+
+        for cap in RE.captures_iter(data.as_str()) {
+            return Ok((client, cap[1].to_string()));
+        }
+
+        Err(std::io::Error::other("Session not found!").into())
+    }
+
+    #[cfg(feature = "web")]
+    fn construct_headers(&self, session: String) -> reqwest::header::HeaderMap {
+        let mut headers = reqwest::header::HeaderMap::new();
+        headers.insert(reqwest::header::USER_AGENT, reqwest::header::HeaderValue::from_static("reqwest"));
+        headers.insert(reqwest::header::COOKIE, reqwest::header::HeaderValue::from_str(format!("XSSID={}", session).as_str()).unwrap());
+        headers
+    }
+
+    #[cfg(feature = "web")]
+    fn http_command(&mut self, client: reqwest::blocking::Client, session: String, params: std::collections::HashMap<&str, &str>) -> Result<(), GS1900Error> {
+        self.check_writable()?;
+        let url = format!("http://{}/cgi-bin/dispatcher.cgi", self.address);
+        let headers = self.construct_headers(session.clone());
+
+        debug!("HTTP command: {:?}", params.iter().filter(|(k, _)| **k != "XSSID").collect::<Vec<_>>());
+        let request = client.post(url.as_str()).form(&params).headers(headers);
+
+        let _response = request.send();
+        trace!("HTTP response: {:?}", _response);
+
+        /*
+         * GS1900 response does not contain an empty line after headers,
+         * which results in an error in the hyper crate (library used by
+         * reqwest to parse the server response). Fortunately we do not
+         * really need the response, so let's just ignore the result.
+         * If hyper crate gets a workaround for the issue, we should check
+         * the HTTP response for success.
+         */
+        //let data = _response.unwrap().text().unwrap();
+
+        Ok(())
+    }
+
+    /// The dispatcher does not report failures, so web settings are read back via SSH
+    #[cfg(feature = "web")]
+    fn web_not_applied(setting: String) -> GS1900Error {
+        GS1900Error::CommandRejected { message: format!("{} was not applied by the web interface", setting) }
+    }
+
+    #[cfg(feature = "web")]
+    pub fn control_poe(&mut self, port: u8, state: bool, priority: PoEPriority, power_mode: PoEPowerMode, range_detection: bool, power_limit_mode: PoELimitMode, power_limit: i32) -> Result<(), GS1900Error> {
+        self.check_writable()?;
+        let (client, session) = self.http_login()?;
+
+        let stateparam = match state {
+            true => "1",
+            false => "0",
+        };
+        let prioparam = match priority {
+            PoEPriority::Critical => "0",
+            PoEPriority::High => "1",
+            PoEPriority::Medium => "2",
+            PoEPriority::Low => "3",
+        };
+        let rangeparam = match range_detection {
+            true => "1",
+            false => "0",
+        };
+        let portparam = format!("{}", port);
+
+        let modeparam = match power_limit_mode {
+            PoELimitMode::Classification => "0",
+            PoELimitMode::User => "0",
+        };
+        if !(1000..=33000).contains(&power_limit) { /* mW */
+            return Err(std::io::Error::new(std::io::ErrorKind::InvalidInput, "Invalid power limit!").into());
+        }
+        let pwrlimitparam = format!("{}", power_limit);
+
+        let pwrmodeparam = match power_mode {
+            PoEPowerMode::IEEE_802_3af => "0",
+            PoEPowerMode::Legacy => "1",
+            PoEPowerMode::Pre_802_3at => "2",
+            PoEPowerMode::IEEE_802_3at => "3",
+        };
+
+        let mut params = std::collections::HashMap::new();
+        params.insert("cmd", "775");
+        params.insert("portlist", portparam.as_str());
+        params.insert("state", stateparam);
+        params.insert("portPriority", prioparam);
+        params.insert("portPowerMode", pwrmodeparam);
+        params.insert("portRangeDetection", rangeparam);
+        params.insert("portLimitMode", modeparam);
+        params.insert("portPowerLimit", pwrlimitparam.as_str());
+        params.insert("poeTimeRange", "20");
+        params.insert("sysSubmit", "Apply");
+        params.insert("XSSID", session.as_str());
+
+        self.http_command(client, session.clone(), params)
+    }
+
+    /// Apply global PoE settings as returned by poe_info(). They are sent as web
+    /// dispatcher cmd 773 (control_poe uses cmd 775 for the port settings). The
+    /// web interface does not report errors, so the settings are read back with
+    /// poe_info() and a mismatch is reported as CommandRejected.
+    #[cfg(feature = "web")]
+    pub fn set_poe_config(&mut self, cfg: &PoEConfig) -> Result<(), GS1900Error> {
+        self.check_writable()?;
+        let (client, session) = self.http_login()?;
+
+        let modeparam = match cfg.management_mode {
+            PoEMode::Classification => "0",
+            PoEMode::Consumption => "1",
+        };
+        let preallocparam = match cfg.pre_allocation {
+            true => "1",
+            false => "0",
+        };
+        let sequenceparam = match cfg.power_up_sequence {
+            PoEPowerUpSequence::Staggered => "0",
+            PoEPowerUpSequence::Simultaneous => "1",
+        };
+
+        let mut params = std::collections::HashMap::new();
+        params.insert("cmd", "773");
+        params.insert("pwrMgmtMode", modeparam);
+        params.insert("preAllocation", preallocparam);
+        params.insert("pwrUpSequence", sequenceparam);
+        params.insert("sysSubmit", "Apply");
+        params.insert("XSSID", session.as_str());
+
+        self.http_command(client, session.clone(), params)?;
 
-            return Ok(Some(mac));
+        let (applied, _, _) = self.poe_info()?;
+        if applied != *cfg {
+            return Err(GS1900::web_not_applied(format!("PoE configuration {:?}", cfg)));
         }
+        Ok(())
+    }
 
-        return Ok(None);
+    /// Add a static MAC table entry (sent as web dispatcher cmd 1291). The web
+    /// interface does not report errors, so the entry is looked up with
+    /// mac_table() afterwards and a missing entry is reported as CommandRejected.
+    #[cfg(feature = "web")]
+    pub fn add_static_mac(&mut self, mac: MacAddress, vlan: u16, port: u8) -> Result<(), GS1900Error> {
+        self.check_writable()?;
+        if !(1..=4094).contains(&vlan) {
+            return Err(std::io::Error::new(std::io::ErrorKind::InvalidInput, "Invalid VLAN ID").into());
+        }
+        if !self.interface_status_int("all")?.iter().any(|x| x.port == port) {
+            return Err(std::io::Error::new(std::io::ErrorKind::InvalidInput, format!("Unknown port {}", port)).into());
+        }
+
+        let (client, session) = self.http_login()?;
+
+        let macparam = format!("{}", mac);
+        let vlanparam = format!("{}", vlan);
+        let portparam = format!("{}", port);
+
+        let mut params = std::collections::HashMap::new();
+        params.insert("cmd", "1291");
+        params.insert("mac", macparam.as_str());
+        params.insert("vid", vlanparam.as_str());
+        params.insert("port", portparam.as_str());
+        params.insert("sysSubmit", "Apply");
+        params.insert("XSSID", session.as_str());
+
+        self.http_command(client, session.clone(), params)?;
+
+        let applied = self.mac_table()?.iter().any(|x| {
+            x.mac_address == mac && x.vlan_id == vlan && x.entry_type == MacEntryType::Static &&
+                x.ports.trim_start_matches(|c: char| c.is_ascii_alphabetic()).parse() == Ok(port)
+        });
+        if !applied {
+            return Err(GS1900::web_not_applied(format!("Static MAC entry {} (VLAN {}, port {})", mac, vlan, port)));
+        }
+        Ok(())
     }
 
-    pub fn poe_debug(&mut self) -> std::io::Result<()> {
-        self.channel.write(b"debug ilpower port status\n")?;
+    /// Remove a static MAC table entry (sent as web dispatcher cmd 1292). The
+    /// entry is looked up with mac_table() afterwards and reported as
+    /// CommandRejected if it is still present.
+    #[cfg(feature = "web")]
+    pub fn delete_static_mac(&mut self, mac: MacAddress, vlan: u16) -> Result<(), GS1900Error> {
+        self.check_writable()?;
+        if !(1..=4094).contains(&vlan) {
+            return Err(std::io::Error::new(std::io::ErrorKind::InvalidInput, "Invalid VLAN ID").into());
+        }
 
-        let raw = self.fetch_data()?;
-        let data = self.clean_data(raw);
+        let (client, session) = self.http_login()?;
 
-        for line in data.split("\n") {
-            if line.len() < 39 {
-                continue;
-            }
-            let port = line[0..4].trim().to_string();
-            let _state = line[5..10].trim().to_string();
-            let status = line[11..21].trim().to_string();
-            let prio = line[22..30].trim().to_string();
-            let class = line[31..38].trim().to_string();
-            let reason = line[39..].trim().to_string();
+        let macparam = format!("{}", mac);
+        let vlanparam = format!("{}", vlan);
 
-            if port.len() < 1 || port == "Port" || port == "----" {
-                continue;
-            }
+        let mut params = std::collections::HashMap::new();
+        params.insert("cmd", "1292");
+        params.insert("mac", macparam.as_str());
+        params.insert("vid", vlanparam.as_str());
+        params.insert("sysSubmit", "Delete");
+        params.insert("XSSID", session.as_str());
 
-            let info = PoEDebug {
-                port: port.parse().map_err(|_| std::io::Error::new(std::io::ErrorKind::InvalidData, "Failed to parse data"))?,
-                status: status.parse()?,
-                priority: prio.parse()?,
-                class: class.parse()?,
-                reason: reason,
-            };
+        self.http_command(client, session.clone(), params)?;
 
-            println!("{:?}", info);
+        if self.mac_table()?.iter().any(|x| x.mac_address == mac && x.vlan_id == vlan && x.entry_type == MacEntryType::Static) {
+            return Err(GS1900::web_not_applied(format!("Deletion of static MAC entry {} (VLAN {})", mac, vlan)));
         }
         Ok(())
     }
 
-    pub fn poe_info(&mut self) -> std::io::Result<(PoEConfig, std::vec::Vec::<PoESupply>, std::vec::Vec::<PoEPort>)> {
-        self.channel.write(b"show power inline consumption\n")?;
+    /// Create a VLAN (sent as web dispatcher cmd 1282 with the VLAN ID in "vid",
+    /// the name in "vlanName" and the member ports as port lists like "1-4,8" in
+    /// "taggedPorts" and "untaggedPorts"). Ports not listed are excluded from the
+    /// VLAN. An empty name keeps the firmware default. The web interface does not
+    /// report errors, so the VLAN is read back with vlan_info() and a mismatch is
+    /// reported as CommandRejected.
+    #[cfg(feature = "web")]
+    pub fn create_vlan(&mut self, id: u16, name: &str, tagged: &[u8], untagged: &[u8]) -> Result<(), GS1900Error> {
+        self.check_writable()?;
+        if !(1..=4094).contains(&id) {
+            return Err(std::io::Error::new(std::io::ErrorKind::InvalidInput, "Invalid VLAN ID").into());
+        }
+        if name.len() > 32 || name.contains(|c: char| c.is_control() || c == '"') {
+            return Err(std::io::Error::new(std::io::ErrorKind::InvalidInput, format!("Invalid VLAN name {:?}", name)).into());
+        }
+        if let Some(port) = tagged.iter().find(|x| untagged.contains(x)) {
+            return Err(std::io::Error::new(std::io::ErrorKind::InvalidInput, format!("Port {} can not be tagged and untagged", port)).into());
+        }
+        let ports = self.interface_status_int("all")?;
+        if let Some(port) = tagged.iter().chain(untagged).find(|p| !ports.iter().any(|x| x.port == **p)) {
+            return Err(std::io::Error::new(std::io::ErrorKind::InvalidInput, format!("Unknown port {}", port)).into());
+        }
 
-        let raw = self.fetch_data()?;
-        let data = self.clean_data(raw);
-        let mut step: u8 = 0;
+        let (client, session) = self.http_login()?;
 
-        let mut cfg = PoEConfig::default();
-        let mut supplies = std::vec::Vec::<PoESupply>::new();
-        let mut portdata = std::vec::Vec::<PoEPort>::new();
+        let vlanparam = format!("{}", id);
+        let taggedparam = PortRange::new(tagged).to_string();
+        let untaggedparam = PortRange::new(untagged).to_string();
 
-        for line in data.split("\n") {
-            if line.trim() == "" {
-                step+=1;
-                continue;
+        let mut params = std::collections::HashMap::new();
+        params.insert("cmd", "1282");
+        params.insert("vid", vlanparam.as_str());
+        params.insert("vlanName", name);
+        params.insert("taggedPorts", taggedparam.as_str());
+        params.insert("untaggedPorts", untaggedparam.as_str());
+        params.insert("sysSubmit", "Apply");
+        params.insert("XSSID", session.as_str());
+
+        self.http_command(client, session.clone(), params)?;
+
+        let sorted = |ports: &[u8]| {
+            let mut x = ports.to_vec();
+            x.sort_unstable();
+            x.dedup();
+            x
+        };
+        let applied = self.vlan_info()?.iter().any(|x| {
+            x.id == u32::from(id) && (name.is_empty() || x.name == name) &&
+                x.ports_tagged_list() == sorted(tagged) && x.ports_untagged_list() == sorted(untagged)
+        });
+        if !applied {
+            return Err(GS1900::web_not_applied(format!("VLAN {}", id)));
+        }
+        Ok(())
+    }
+
+    /// Delete a VLAN (sent as web dispatcher cmd 1284 with the VLAN ID in "vid").
+    /// The default VLAN 1 can not be deleted. A VLAN still listed by vlan_info()
+    /// afterwards is reported as CommandRejected.
+    #[cfg(feature = "web")]
+    pub fn delete_vlan(&mut self, id: u16) -> Result<(), GS1900Error> {
+        self.check_writable()?;
+        if id == 1 {
+            return Err(std::io::Error::new(std::io::ErrorKind::InvalidInput, "The default VLAN can not be deleted").into());
+        }
+        if !(2..=4094).contains(&id) {
+            return Err(std::io::Error::new(std::io::ErrorKind::InvalidInput, "Invalid VLAN ID").into());
+        }
+
+        let (client, session) = self.http_login()?;
+
+        let vlanparam = format!("{}", id);
+
+        let mut params = std::collections::HashMap::new();
+        params.insert("cmd", "1284");
+        params.insert("vid", vlanparam.as_str());
+        params.insert("sysSubmit", "Delete");
+        params.insert("XSSID", session.as_str());
+
+        self.http_command(client, session.clone(), params)?;
+
+        if self.vlan_info()?.iter().any(|x| x.id == u32::from(id)) {
+            return Err(GS1900::web_not_applied(format!("Deletion of VLAN {}", id)));
+        }
+        Ok(())
+    }
+
+    /// Set the PVID (untagged ingress VLAN) of a port via the web interface
+    /// (sent as web dispatcher cmd 1286 with the port in "portlist" and the VLAN
+    /// ID in "pvid"). Unlike set_port_pvid() this does not check that the VLAN
+    /// exists. The web interface does not report errors, so the PVID is read
+    /// back via SSH and a mismatch is reported as CommandRejected.
+    #[cfg(feature = "web")]
+    pub fn control_pvid(&mut self, port: u8, vlan: u16) -> Result<(), GS1900Error> {
+        self.check_writable()?;
+        if port == 0 {
+            return Err(std::io::Error::new(std::io::ErrorKind::InvalidInput, "Invalid port").into());
+        }
+        if !(1..=4094).contains(&vlan) {
+            return Err(std::io::Error::new(std::io::ErrorKind::InvalidInput, "Invalid VLAN ID").into());
+        }
+
+        let (client, session) = self.http_login()?;
+
+        let portparam = format!("{}", port);
+        let vlanparam = format!("{}", vlan);
+
+        let mut params = std::collections::HashMap::new();
+        params.insert("cmd", "1286");
+        params.insert("portlist", portparam.as_str());
+        params.insert("pvid", vlanparam.as_str());
+        params.insert("sysSubmit", "Apply");
+        params.insert("XSSID", session.as_str());
+
+        self.http_command(client, session.clone(), params)?;
+
+        match self.switchports(portparam.as_str())?.get(&port) {
+            Some(x) if x.pvid == vlan => Ok(()),
+            _ => Err(GS1900::web_not_applied(format!("PVID {} on port {}", vlan, port))),
+        }
+    }
+
+    #[cfg(feature = "web")]
+    pub fn control_port(&mut self, port: u8, label: String, enabled: bool, speed: PortSpeed, duplex: PortDuplex, flow_control: bool) -> Result<(), GS1900Error> {
+        self.check_writable()?;
+        let (client, session) = self.http_login()?;
+
+        let portparam = format!("{}", port);
+
+        let stateparam = match enabled {
+            true => "1",
+            false => "0",
+        };
+
+        let speedparam: &str;
+        if speed.auto {
+            speedparam = "0";
+        } else if speed.speed >= 1000 {
+            speedparam = "3";
+        } else if speed.speed >= 100 {
+            speedparam = "2";
+        } else if speed.speed >= 10 {
+            speedparam = "1";
+        } else {
+            speedparam = "0";
+        }
+
+        let duplexparam = match duplex {
+            PortDuplex::Auto => "0",
+            PortDuplex::Full => "1",
+            PortDuplex::Half => "2",
+        };
+
+        let fcparam = match flow_control {
+            true => "1",
+            false => "0",
+        };
+
+        let mut params = std::collections::HashMap::new();
+        params.insert("cmd", "770");
+        params.insert("portlist", portparam.as_str());
+        params.insert("descp", label.as_str());
+        params.insert("state", stateparam);
+        params.insert("speed", speedparam);
+        params.insert("duplex", duplexparam);
+        params.insert("fc", fcparam);
+        params.insert("sysSubmit", "Apply");
+        params.insert("XSSID", session.as_str());
+
+        self.http_command(client, session.clone(), params)
+    }
+}
+
+/// libssh2 error types which can report that a non-blocking call would block
+#[cfg(feature = "async")]
+trait WouldBlock {
+    fn would_block(&self) -> bool;
+}
+
+#[cfg(feature = "async")]
+impl WouldBlock for ssh2::Error {
+    fn would_block(&self) -> bool {
+        /* LIBSSH2_ERROR_EAGAIN */
+        self.code() == ssh2::ErrorCode::Session(-37)
+    }
+}
+
+#[cfg(feature = "async")]
+impl WouldBlock for std::io::Error {
+    fn would_block(&self) -> bool {
+        self.kind() == std::io::ErrorKind::WouldBlock
+    }
+}
+
+/// Run a libssh2 call on a non-blocking session, waiting for the socket as
+/// long as the call would block
+#[cfg(feature = "async")]
+async fn ssh_io<T, E, F>(tcp: &tokio::net::TcpStream, session: &ssh2::Session, mut op: F) -> Result<T, GS1900Error>
+    where E: WouldBlock + Into<GS1900Error>,
+          F: FnMut() -> Result<T, E> {
+    match op() {
+        Err(e) if e.would_block() => {},
+        x => return x.map_err(|e| e.into()),
+    }
+
+    loop {
+        let interest = match session.block_directions() {
+            ssh2::BlockDirections::Outbound => tokio::io::Interest::WRITABLE,
+            ssh2::BlockDirections::Both => tokio::io::Interest::READABLE | tokio::io::Interest::WRITABLE,
+            _ => tokio::io::Interest::READABLE,
+        };
+        let ready = tcp.ready(interest).await?;
+        let interest = if ready.is_readable() { tokio::io::Interest::READABLE } else { tokio::io::Interest::WRITABLE };
+
+        /* try_io clears the readiness if libssh2 still could not make progress */
+        let mut result = None;
+        let io = tcp.try_io(interest, || match op() {
+            Err(e) if e.would_block() => Err(std::io::ErrorKind::WouldBlock.into()),
+            x => {
+                result = Some(x);
+                Ok(())
+            },
+        });
+        match (io, result) {
+            (_, Some(x)) => return x.map_err(|e| e.into()),
+            (Err(e), None) if e.kind() != std::io::ErrorKind::WouldBlock => return Err(e.into()),
+            _ => {},
+        }
+    }
+}
+
+/// Async access to a GS1900 switch for use from a tokio runtime
+///
+/// The SSH session runs in non-blocking mode on a socket registered with
+/// tokio, so waiting for the switch does not occupy a thread and many
+/// switches can be polled concurrently. The command output is parsed by the
+/// same functions as in GS1900. Only the commonly polled status information
+/// is available, configuration changes need GS1900.
+#[cfg(feature = "async")]
+pub struct AsyncGS1900 {
+    config: ConnectionConfig,
+    tcp: tokio::net::TcpStream,
+    session: ssh2::Session,
+    channel: ssh2::Channel,
+    prompt: String,
+    last_output: String,
+    last_command: String,
+    sfp_ports: Option<std::vec::Vec<u8>>,
+}
+
+#[cfg(feature = "async")]
+impl AsyncGS1900 {
+    /// Access the device using shared connection parameters
+    pub async fn connect(address: &str, config: &ConnectionConfig) -> Result<AsyncGS1900, GS1900Error> {
+        let addr = format!("{}:{}", address, config.ssh_port);
+        let mut tcp = Err(std::io::Error::new(std::io::ErrorKind::InvalidInput, format!("Could not resolve {}", address)));
+        for sockaddr in tokio::net::lookup_host(addr).await? {
+            tcp = match tokio::time::timeout(config.connect_timeout, tokio::net::TcpStream::connect(sockaddr)).await {
+                Ok(x) => x,
+                Err(_) => Err(std::io::Error::new(std::io::ErrorKind::TimedOut, format!("Connecting to {} timed out", sockaddr))),
+            };
+            if tcp.is_ok() {
+                break;
             }
-            match step {
-                0 => {
-                    let kv: Vec<&str> = line.split(":").collect();
-                    if kv.len() < 2 {
-                        continue;
-                    }
-                    let key = kv[0].trim();
-                    let val = kv[1].trim();
-
-                    match key {
-                        "Power management mode" => cfg.management_mode = val.parse()?,
-                        "Pre-allocation" => cfg.pre_allocation = val == "Enabled",
-                        "Power-up sequence" => cfg.power_up_sequence = val.parse()?,
-                        _ => { return Err(std::io::Error::new(std::io::ErrorKind::Other, "Received invalid data")); },
-                    }
-                },
-                1 => {
-                    //Unit Power Status Nominal  Allocated       Consumed Available
-                    //                  Power    Power           Power    Power
-                    //---- ----- ------ -------- --------------- -------- ---------
-                    if line.len() < 52 {
-                        continue;
-                    }
-                    let unit: u8 = match line[0..4].trim().parse() {
-                        Ok(x) => x,
-                        Err(_fail) => { continue; },
-                    };
-                    let power = line[5..10].trim();
-                    let status = line[11..17].trim();
-                    let nom_pwr = line[18..26].trim().replace("Watts", "");
-                    let alo_pwr = line[27..42].trim().split(" ").collect::<Vec<&str>>()[0].replace("Watts", "");
-                    let con_pwr = line[43..51].trim().replace("Watts", "");
-                    let ava_pwr = line[52..].trim().replace("Watts", "");
-
-                    let supply = PoESupply {
-                        unit: unit,
-                        power: power.to_string(),
-                        status: status.to_string(),
-                        nominal_power: nom_pwr.parse().map_err(|_| std::io::Error::new(std::io::ErrorKind::InvalidData, "Failed to parse data"))?,
-                        allocated_power: alo_pwr.parse().map_err(|_| std::io::Error::new(std::io::ErrorKind::InvalidData, "Failed to parse data"))?,
-                        consumed_power: con_pwr.parse().map_err(|_| std::io::Error::new(std::io::ErrorKind::InvalidData, "Failed to parse data"))?,
-                        available_power: ava_pwr.parse().map_err(|_| std::io::Error::new(std::io::ErrorKind::InvalidData, "Failed to parse data"))?,
-                    };
-                    supplies.push(supply);
+        }
+
+        /* libssh2 works on its own handle of the socket, tokio only reports the readiness */
+        let tcp = tcp?.into_std()?;
+        let ssh_tcp = tcp.try_clone()?;
+        let tcp = tokio::net::TcpStream::from_std(tcp)?;
+
+        let mut sess = Session::new()?;
+        sess.set_tcp_stream(ssh_tcp);
+        sess.set_blocking(false);
+
+        let login = AsyncGS1900::open_shell(&tcp, &sess, config);
+        let (channel, prompt) = match tokio::time::timeout(config.connect_timeout, login).await {
+            Ok(x) => x?,
+            Err(_) => return Err(GS1900Error::Timeout { operation: format!("login to {}", address) }),
+        };
+
+        Ok(AsyncGS1900 {
+            config: config.clone(),
+            tcp,
+            session: sess,
+            channel,
+            prompt,
+            last_output: String::new(),
+            last_command: String::new(),
+            sfp_ports: None,
+        })
+    }
+
+    async fn open_shell(tcp: &tokio::net::TcpStream, sess: &ssh2::Session, config: &ConnectionConfig) -> Result<(ssh2::Channel, String), GS1900Error> {
+        let mut handshake = sess.clone();
+        ssh_io(tcp, sess, || handshake.handshake()).await?;
+        match (&config.private_key, &config.password) {
+            (Some((key, passphrase)), _) => ssh_io(tcp, sess, || sess.userauth_pubkey_file(&config.username, None, key, passphrase.as_deref())).await?,
+            (None, Some(password)) => ssh_io(tcp, sess, || sess.userauth_password(&config.username, password)).await?,
+            (None, None) => return Err(GS1900Error::PasswordRequired),
+        }
+
+        let mut chan = ssh_io(tcp, sess, || sess.channel_session()).await?;
+        ssh_io(tcp, sess, || chan.shell()).await?;
+
+        let mut data = String::new();
+        let prompt = loop {
+            let mut buffer = [0; 100];
+            let len = ssh_io(tcp, sess, || chan.read(&mut buffer)).await?;
+            if len == 0 && chan.eof() {
+                return Err(GS1900Error::UnexpectedPrompt);
+            }
+            data += &String::from_utf8_lossy(&buffer[0..len]);
+
+            if let Some(prompt) = find_prompt(&data) {
+                break prompt;
+            }
+        };
+
+        if config.strict && !data.as_bytes().starts_with(&[27, 91, 72, 27, 91, 74, 0]) {
+            return Err(GS1900Error::UnexpectedPrompt);
+        }
+
+        Ok((chan, prompt))
+    }
+
+    /// Cleaned output of the last command, useful to debug parser failures
+    pub fn last_output(&self) -> &str {
+        self.last_output.as_str()
+    }
+
+    async fn write_all(&mut self, data: &[u8]) -> Result<(), GS1900Error> {
+        let mut pos = 0;
+        while pos < data.len() {
+            let channel = &mut self.channel;
+            pos += ssh_io(&self.tcp, &self.session, || channel.write(&data[pos..])).await?;
+        }
+        Ok(())
+    }
+
+    /// Send a command to the switch
+    async fn write_command(&mut self, cmd: &str) -> Result<(), GS1900Error> {
+        check_command(cmd)?;
+        debug!("command: {}", cmd);
+        self.write_all(format!("{}\n", cmd).as_bytes()).await?;
+        self.last_command = cmd.to_string();
+        Ok(())
+    }
+
+    /// Read command output until the prompt appears and nothing follows it
+    async fn fetch_data(&mut self) -> Result<String, GS1900Error> {
+        let mut data = String::new();
+        let mut pressed = false;
+        loop {
+            /* after a key press the pager line stays until the next page arrives */
+            let timeout = if pressed { self.config.command_timeout } else { output_wait(&data, &self.prompt, self.config.command_timeout) };
+
+            let mut buffer = [0; 100];
+            let channel = &mut self.channel;
+            let read = ssh_io(&self.tcp, &self.session, || channel.read(&mut buffer));
+            let len = match tokio::time::timeout(timeout, read).await {
+                Ok(Ok(x)) => x,
+                Ok(Err(e)) => {
+                    error!("unexpected data: {:?}", data.as_bytes());
+                    debug!("read failed: {}", e);
+                    return Err(truncated(&self.last_command, &data));
                 },
-                2 => {
-                    //Port Power Limit (Admin) (mW) Power (mW) Voltage (mV) Current (mA)
-                    //---- ------------------------ ---------- ------------ ------------
-                    if line.len() < 54 {
+                Err(_) => match output_idle(&data, &self.prompt) {
+                    Some(Idle::Done) => break,
+                    Some(Idle::PressKey) if !pressed => {
+                        self.write_all(b" ").await?;
+                        pressed = true;
                         continue;
-                    }
-                    let port: u8 = match line[0..4].trim().parse() {
-                        Ok(x) => x,
-                        Err(_fail) => { continue; },
-                    };
-                    let both_pwr_limit = line[5..29].trim();
-                    let pwr_limit_split: Vec<&str> = both_pwr_limit[0..both_pwr_limit.len()-1].split("(").collect();
-                    let pwr_limit: i32 = pwr_limit_split[0].trim().parse().map_err(|_| std::io::Error::new(std::io::ErrorKind::InvalidData, "Failed to parse data"))?;
-                    let admin_pwr_limit: i32 = pwr_limit_split[1].trim().parse().map_err(|_| std::io::Error::new(std::io::ErrorKind::InvalidData, "Failed to parse data"))?;
-                    let pwr: i32 = line[30..40].trim().parse().map_err(|_| std::io::Error::new(std::io::ErrorKind::InvalidData, "Failed to parse data"))?;
-                    let volt: i32 = line[41..53].trim().parse().map_err(|_| std::io::Error::new(std::io::ErrorKind::InvalidData, "Failed to parse data"))?;
-                    let current: i32 = line[54..].trim().parse().map_err(|_| std::io::Error::new(std::io::ErrorKind::InvalidData, "Failed to parse data"))?;
-
-                    let portinfo = PoEPort {
-                        port: port,
-                        power_limit: pwr_limit,
-                        admin_power_limit: admin_pwr_limit,
-                        power: pwr,
-                        voltage: volt,
-                        current: current,
-                    };
-                    portdata.push(portinfo);
+                    },
+                    _ => {
+                        error!("timeout, unexpected data: {:?}", data.as_bytes());
+                        return Err(GS1900Error::Timeout {
+                            operation: format!("output of '{}' ({} bytes received)", self.last_command, data.len()),
+                        });
+                    },
                 },
-                _ => {},
+            };
+
+            /* connection closed before the prompt appeared */
+            if len == 0 && self.channel.eof() {
+                return Err(truncated(&self.last_command, &data));
             }
+
+            data += &String::from_utf8_lossy(&buffer[0..len]);
+            pressed = false;
         }
 
-        return Ok((cfg, supplies, portdata));
+        let result = clean_output(&data, &self.prompt);
+        trace!("response: {:?}", result);
+        self.last_output = result.clone();
+
+        check_output(&self.last_command, &data)?;
+        Ok(result)
     }
 
-    pub fn cable_info(&mut self) -> std::io::Result<std::vec::Vec::<CableDiagnosis>> {
-        return self.cable_info_int("all");
+    /// Run a command and return its cleaned output
+    async fn command(&mut self, cmd: &str) -> Result<String, GS1900Error> {
+        self.write_command(cmd).await?;
+        self.fetch_data().await
     }
 
-    pub fn cable_info_port(&mut self, port: u8) -> std::io::Result<std::option::Option<CableDiagnosis>> {
-        let res = self.cable_info_int(format!("{}", port).as_str());
-        return match res {
-            Ok(x) => {
-                if x.len() <= 0 {
-                    return Ok(None);
-                }
-                let e = x[0];
-                return Ok(Some(e));
-            },
-            Err(e) => Err(e),
-        };
+    pub async fn basic_info(&mut self) -> Result<BasicInfo, GS1900Error> {
+        let data = self.command("show info").await?;
+        parse_basic_info(&data)
     }
 
-    fn cable_info_int(&mut self, interfaces: &str) -> std::io::Result<std::vec::Vec::<CableDiagnosis>> {
-        self.channel.write(format!("show cable-diag interfaces {}\n", interfaces).as_bytes())?;
-        let mut result = std::vec::Vec::<CableDiagnosis>::new();
+    pub async fn lldp_info(&mut self) -> Result<std::vec::Vec::<LLDPNeighbor>, GS1900Error> {
+        let data = self.command("show lldp neighbor").await?;
+        parse_lldp_neighbors(&data)
+    }
 
-        let raw = self.fetch_data()?;
-        let data = self.clean_data(raw);
+    pub async fn fiber_info(&mut self) -> Result<std::vec::Vec::<FiberInfo>, GS1900Error> {
+        let data = self.command("show fiber-transceiver interfaces all").await?;
+        parse_fiber_info(&data)
+    }
 
-        let mut diag = CableDiagnosis::default();
+    pub async fn mac_table(&mut self) -> Result<std::vec::Vec::<MacEntry>, GS1900Error> {
+        let data = self.command("show mac address-table").await?;
+        parse_mac_table(&data)
+    }
 
-        for line in data.split("\n") {
-            let fields: Vec<&str> = line.split("|").collect();
-            if fields.len() == 5 && fields[0].trim() != "Port" {
-                let port: u8 = fields[0].trim().parse().map_err(|_| std::io::Error::new(std::io::ErrorKind::InvalidData, "Failed to parse data"))?;
-                let speed: String = fields[1].trim().to_string();
-                let pair: String = fields[2].trim().replace("Pair ", "").to_string();
-                let pairc: char = pair.chars().next().ok_or_else(|| std::io::Error::new(std::io::ErrorKind::InvalidData, "Failed to parse data"))?;
-                let length: u32 = fields[3].trim().replace(".", "").parse().map_err(|_| std::io::Error::new(std::io::ErrorKind::InvalidData, "Failed to parse data"))?;
-                let status: String = fields[4].trim().to_string();
-                diag.port = port;
-                diag.speed = speed.parse()?;
-                diag.pair_info[0].pair = pairc;
-                diag.pair_info[0].length = length;
-                diag.pair_info[0].status =status.parse::<CablePairState>()?;
-            } else if fields.len() == 3 {
-                let pair: String = fields[0].trim().replace("Pair ", "").to_string();
-                let length: u32 = fields[1].trim().replace(".", "").parse().map_err(|_| std::io::Error::new(std::io::ErrorKind::InvalidData, "Failed to parse data"))?;
-                let status: String = fields[2].trim().to_string();
-                let pairc: char = pair.chars().next().ok_or_else(|| std::io::Error::new(std::io::ErrorKind::InvalidData, "Failed to parse data"))?;
-                let offset = match pairc { 'A' => 0, 'B' => 1, 'C' => 2, 'D' => 3, _ => 4 };
-                if offset > 3 { continue }
-                diag.pair_info[offset].pair = pairc;
-                diag.pair_info[offset].length = length;
-                diag.pair_info[offset].status = status.parse::<CablePairState>()?;
-            } else if line.trim() == "" {
-                if diag.port > 0 {
-                    result.push(diag);
-                }
-                diag = CableDiagnosis::default();
-            }
-        }
+    pub async fn poe_info(&mut self) -> Result<(PoEConfig, std::vec::Vec::<PoESupply>, std::vec::Vec::<PoEPort>), GS1900Error> {
+        let data = self.command("show power inline consumption").await?;
+        parse_poe_info(&data)
+    }
 
-        return Ok(result);
+    pub async fn cable_info(&mut self) -> Result<std::vec::Vec::<CableDiagnosis>, GS1900Error> {
+        let data = self.command("show cable-diag interfaces all").await?;
+        parse_cable_diag(&data)
     }
 
-    pub fn interface_info(&mut self) -> std::io::Result<std::vec::Vec::<InterfaceTrafficStatus>> {
-        return self.interface_info_int("all");
+    pub async fn interface_info(&mut self) -> Result<std::vec::Vec::<InterfaceTrafficStatus>, GS1900Error> {
+        let data = self.command("show interfaces all").await?;
+        parse_interfaces(&data, false)
     }
 
-    pub fn interface_info_port(&mut self, port: u8) -> std::io::Result<InterfaceTrafficStatus> {
-        let ret = self.interface_info_int(format!("{}", port).as_str());
-        return match ret {
-            Err(x) => Err(x),
-            Ok(x) => {
-                if x.len() <= 0 {
-                    Err(std::io::Error::new(std::io::ErrorKind::Other, "Port not found"))
-                } else {
-                    Ok(x[0])
-                }
+    /// Recent link utilization of every port
+    pub async fn interface_utilization(&mut self) -> Result<std::vec::Vec::<PortUtilization>, GS1900Error> {
+        let data = match self.command("show interfaces utilization").await {
+            Err(GS1900Error::CommandRejected { .. }) => {
+                return Err(GS1900Error::Unsupported { feature: "interface utilization".to_string() });
             },
-        }
+            x => x?,
+        };
+        parse_utilization(&data)
     }
 
-    fn interface_info_int(&mut self, interfaces: &str) -> std::io::Result<std::vec::Vec::<InterfaceTrafficStatus>> {
-        self.channel.write(format!("show interfaces {}\n", interfaces).as_bytes())?;
-        let mut result = std::vec::Vec::<InterfaceTrafficStatus>::new();
-
-        let raw = self.fetch_data()?;
-        let data = self.clean_data(raw);
+    pub async fn interface_status_info(&mut self) -> Result<std::vec::Vec::<InterfaceStatus>, GS1900Error> {
+        let data = self.command("show interfaces all status").await?;
+        let mut result = parse_interface_status(&data)?;
+        let data = self.command("show interfaces all switchport").await?;
+        let switchports = parse_switchports(&data)?;
+        let sfp_ports = self.sfp_ports().await?;
 
-        let mut status = InterfaceTrafficStatus::default();
-
-        for line in data.split("\n") {
-            if line.starts_with("     ") {
-                lazy_static! {
-                    static ref RE1: Regex = Regex::new(r"(\d+) packets input, (\d+) bytes, (\d+) throttles").unwrap();
-                    static ref RE2: Regex = Regex::new(r"Received (\d+) broadcasts \((\d+) multicasts\)").unwrap();
-                    static ref RE3: Regex = Regex::new(r"(\d+) runts, (\d+) giants, (\d+) throttles").unwrap();
-                    static ref RE4: Regex = Regex::new(r"(\d+) input errors, (\d+) CRC, (\d+) frame, (\d+) overrun, (\d+) ignored").unwrap();
-                    static ref RE5: Regex = Regex::new(r"(\d+) multicast, (\d+) pause input").unwrap();
-                    static ref RE6: Regex = Regex::new(r"(\d+) input packets with dribble condition detected").unwrap();
-                    static ref RE7: Regex = Regex::new(r"(\d+) packets output, (\d+) bytes, (\d+) underrun").unwrap();
-                    static ref RE8: Regex = Regex::new(r"(\d+) output errors, (\d+) collisions, (\d+) interface resets").unwrap();
-                    static ref RE9: Regex = Regex::new(r"(\d+) babbles, (\d+) late collision, (\d+) deferred").unwrap();
-                    static ref RE10: Regex = Regex::new(r"(\d+) PAUSE output").unwrap();
-                }
-                for cap in RE1.captures_iter(line) {
-                    status.input_packets = cap[1].parse().unwrap();
-                    status.input_bytes = cap[2].parse().unwrap();
-                    status.input_throttles = cap[3].parse().unwrap();
-                }
-                for cap in RE2.captures_iter(line) {
-                    status.input_broadcasts = cap[1].parse().unwrap();
-                    status.input_multicasts = cap[2].parse().unwrap();
-                }
-                for cap in RE3.captures_iter(line) {
-                    status.input_runts = cap[1].parse().unwrap();
-                    status.input_giants = cap[2].parse().unwrap();
-                }
-                for cap in RE4.captures_iter(line) {
-                    status.input_errors = cap[1].parse().unwrap();
-                    status.input_crc = cap[2].parse().unwrap();
-                    status.input_frame = cap[3].parse().unwrap();
-                    status.input_overrun = cap[4].parse().unwrap();
-                    status.input_ignored = cap[5].parse().unwrap();
-                }
-                for cap in RE5.captures_iter(line) {
-                    status.input_pause = cap[2].parse().unwrap();
-                }
-                for cap in RE6.captures_iter(line) {
-                    status.input_dribble = cap[1].parse().unwrap();
-                }
-                for cap in RE7.captures_iter(line) {
-                    status.output_packets = cap[1].parse().unwrap();
-                    status.output_bytes = cap[2].parse().unwrap();
-                    status.output_underrun = cap[3].parse().unwrap();
-                }
-                for cap in RE8.captures_iter(line) {
-                    status.output_errors = cap[1].parse().unwrap();
-                    status.output_collisions = cap[2].parse().unwrap();
-                    status.output_interface_resets = cap[3].parse().unwrap();
-                }
-                for cap in RE9.captures_iter(line) {
-                    status.output_babbles = cap[1].parse().unwrap();
-                    status.output_late_collisions = cap[2].parse().unwrap();
-                    status.output_deferred = cap[3].parse().unwrap();
-                }
-                for cap in RE10.captures_iter(line) {
-                    status.output_paused =  cap[1].parse().unwrap();
-                    if status.port > 0 {
-                        result.push(status);
-                        status = InterfaceTrafficStatus::default();
-                    }
-                }
-            } else if line.starts_with("  ") {
-                if line.contains("media type is") {
-                    let splitted: Vec<&str> = line.split(", ").collect();
-                    status.duplex = splitted[0].trim().replace("-duplex", "").to_string().parse()?;
-                    status.speed = splitted[1].trim().replace("-speed", "").to_string().parse()?;
-                    status.media_type = splitted[2][14..].parse()?;
-                } else if line.contains("flow-control is") {
-                    status.flow_control = line[16..].contains("on");
-                }
-            } else if line.starts_with("GigabitEthernet") {
-                let splitted: Vec<&str> = line[15..].split(" ").collect();
-                status.port = splitted[0].parse().map_err(|_| std::io::Error::new(std::io::ErrorKind::InvalidData, "Failed to parse data"))?;
-                status.up = splitted[2] == "up";
+        for interface in result.iter_mut() {
+            if let Some(sp) = switchports.get(&interface.port) {
+                interface.mode = sp.mode;
             }
+            interface.is_uplink = sfp_ports.contains(&interface.port);
         }
 
-        return Ok(result);
+        Ok(result)
     }
 
-    pub fn interface_status_info(&mut self) -> std::io::Result<std::vec::Vec::<InterfaceStatus>> {
-        self.channel.write(b"show interfaces all status\n")?;
-        let mut result = std::vec::Vec::<InterfaceStatus>::new();
-
-        lazy_static! {
-            static ref RE: Regex = Regex::new(r"^(\d+)[ ]+(.*?)[ ]+(notconnect|connected)[ ]+(\d+)[ ]+([^ ]+)[ ]+([^ ]+)[ ]+(Copper|Fiber)$").unwrap();
+    /// Ports with an SFP cage (probed once, then cached)
+    async fn sfp_ports(&mut self) -> Result<std::vec::Vec<u8>, GS1900Error> {
+        if let Some(x) = &self.sfp_ports {
+            return Ok(x.clone());
         }
 
-        let raw = self.fetch_data()?;
-        let data = self.clean_data(raw);
+        let result = match self.command("show fiber-transceiver interfaces all").await {
+            Ok(data) => parse_sfp_ports(&data)?,
+            /* models without SFP ports do not know the command */
+            Err(GS1900Error::CommandRejected { .. }) => std::vec::Vec::new(),
+            Err(e) => return Err(e),
+        };
 
-        for line in data.split("\n") {
-            for cap in RE.captures_iter(line) {
-                let interface = InterfaceStatus {
-                    port: cap[1].parse().unwrap(),
-                    name: cap[2].to_string(),
-                    connected: &cap[3] == "connected",
-                    vlan: cap[4].parse().unwrap(),
-                    duplex: cap[5].parse()?,
-                    speed: cap[6].parse()?,
-                    mediatype: cap[7].parse()?,
-                };
-                result.push(interface);
-            }
-        }
+        self.sfp_ports = Some(result.clone());
         Ok(result)
     }
 
-    pub fn vlan_info(&mut self) -> std::io::Result<std::vec::Vec::<VLANInfo>> {
-        self.channel.write(b"show vlan\n")?;
-        let mut result = std::vec::Vec::<VLANInfo>::new();
+    pub async fn vlan_info(&mut self) -> Result<std::vec::Vec::<VLANInfo>, GS1900Error> {
+        let data = self.command("show vlan").await?;
+        parse_vlans(&data)
+    }
 
-        let raw = self.fetch_data()?;
-        let data = self.clean_data(raw);
+    pub async fn alarms(&mut self) -> Result<std::vec::Vec::<Alarm>, GS1900Error> {
+        let data = self.command("show alarm").await?;
+        parse_alarms(&data)
+    }
 
-        for line in data.split("\n") {
-            let elements: std::vec::Vec<&str> = line.split("|").collect();
-            if elements.len() < 5 || elements[0].trim() == "VID" {
-                continue;
-            }
+    /// Reason of the last reboot (Unknown if the firmware does not record it)
+    pub async fn last_reboot(&mut self) -> Result<RebootInfo, GS1900Error> {
+        match self.command("show reboot-cause").await {
+            Ok(data) => Ok(parse_reboot_cause(&data)),
+            Err(GS1900Error::CommandRejected { .. }) => Ok(parse_reboot_cause("")),
+            Err(e) => Err(e),
+        }
+    }
 
-            let vlan = VLANInfo {
-                id: elements[0].trim().parse().map_err(|_| std::io::Error::new(std::io::ErrorKind::InvalidData, "Failed to parse data"))?,
-                name: elements[1].trim().to_string(),
-                ports_untagged: elements[2].trim().to_string(),
-                ports_tagged: elements[3].trim().to_string(),
-                vlan_type: elements[4].trim().parse()?,
-            };
+    pub async fn nop(&mut self) -> Result<(), GS1900Error> {
+        self.command("").await?;
+        Ok(())
+    }
+}
 
-            result.push(vlan);
-        }
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MAC_TABLE: &str = "show mac address-table\r\n \
+  VID  |    MAC Address    |   Type   |  Ports\r\n\
+-------+-------------------+----------+---------\r\n    \
+1 | 00:11:22:33:44:55 | Dynamic  | gi1\r\n \
+3000 | 00:11:22:33:44:66 | Static   | gi2\r\n\
+\r\n\
+switch#";
+
+    #[test]
+    fn table_complete_accepts_full_table() {
+        assert!(table_complete(MAC_TABLE));
+        assert!(table_complete("show info\r\nSystem Name : switch\r\nswitch#"));
+    }
 
-        Ok(result)
+    #[test]
+    fn table_complete_detects_partial_row() {
+        let truncated = "show mac address-table\r\n \
+  VID  |    MAC Address    |   Type   |  Ports\r\n\
+-------+-------------------+----------+---------\r\n    \
+1 | 00:11:22:33:44:55 | Dynamic  | gi1\r\n \
+3000 | 00:11:22\r\n\
+switch#";
+        assert!(!table_complete(truncated));
     }
 
-    pub fn nop(&mut self) -> std::io::Result<()> {
-        self.channel.write(b"\n")?;
-        self.fetch_data()?;
-        Ok(())
+    #[test]
+    fn is_prompt_matches_whole_line() {
+        assert!(is_prompt("switch#", "switch#"));
+        assert!(is_prompt("\rswitch# ", "switch# "));
+        /* indented output and descriptions containing the hostname */
+        assert!(!is_prompt("  switch#", "switch#"));
+        assert!(!is_prompt("switch#1", "switch#"));
+        assert!(!is_prompt("my-switch#", "switch#"));
     }
 
-    #[cfg(feature = "web")]
-    fn zyxel_password(&self) -> String {
-        let alphabetstr = "ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789";
-        let alphabet:Vec<char> = alphabetstr.chars().collect();
-        let pwchars:Vec<char> = self.password.chars().collect();
-        let mut result = String::new();
-        let mut i: i32 = self.password.len() as i32;
-        i -= 1;
+    #[test]
+    fn parse_pipe_table_skips_header_and_short_rows() {
+        let rows: Vec<Vec<&str>> = parse_pipe_table(MAC_TABLE, 4, "VID").collect();
+        assert_eq!(rows, vec![
+            vec!["1", "00:11:22:33:44:55", "Dynamic", "gi1"],
+            vec!["3000", "00:11:22:33:44:66", "Static", "gi2"],
+        ]);
+
+        /* unit rows have an empty first column */
+        let data = "Port | Temperature | Voltage\n     | (C)         | (V)\n   1 | 35.50       | 3.30\n";
+        let rows: Vec<Vec<&str>> = parse_pipe_table(data, 3, "Port").collect();
+        assert_eq!(rows, vec![vec!["1", "35.50", "3.30"]]);
+    }
 
-        for x in 0..320 {
-            if x % 7 == 6 && i >= 0 {
-                result += format!("{}", pwchars[i as usize]).as_str();
-                i-=1;
-            } else if x == 122 {
-                if self.password.len() < 10 {
-                    result += "0"
-                } else {
-                    let c = format!("{}", self.password.len()/10).chars().next().unwrap();
-                    result += format!("{}", c).as_str()
-                }
-            } else if x == 288 {
-                result += format!("{}", self.password.len()%10).as_str()
-            } else {
-                let rnd = random_integer::random_u8(0, (alphabet.len() as u8)-1);
-                result += format!("{}", alphabet[rnd as usize]).as_str()
-            }
+    #[test]
+    fn check_rejected_reports_cli_errors() {
+        assert!(check_rejected("show vlan\r\n  VID | Name\r\n").is_ok());
+        match check_rejected("show vlna\r\n                ^\r\n% Invalid input detected at '^' marker.\r\n") {
+            Err(GS1900Error::CommandRejected { message }) => assert_eq!(message, "Invalid input detected at '^' marker."),
+            x => panic!("unexpected result {:?}", x),
         }
+    }
 
-        result
+    const INTERFACES: &str = "show interfaces 1
+GigabitEthernet1 is up
+  Hardware is Gigabit Ethernet
+  Full-duplex, 1000Mb/s-speed, media type is Copper
+  flow-control is on (negotiated off)
+  5 minute input rate 15000 bits/sec, 20 packets/sec
+  5 minute output rate 8000 bits/sec, 10 packets/sec
+     1234 packets input, 567890 bytes, 0 throttles
+     Received 12 broadcasts (34 multicasts)
+     0 runts, 2 giants, 0 throttles
+     3 input errors, 1 CRC, 0 frame, 0 overrun, 0 ignored
+     34 multicast, 0 pause input
+     0 input packets with dribble condition detected
+     4321 packets output, 98765 bytes, 0 underrun
+     0 output errors, 0 collisions, 1 interface resets
+     0 babbles, 0 late collision, 0 deferred
+     0 PAUSE output
+";
+
+    #[test]
+    fn parse_interfaces_reads_rates_and_totals() {
+        let result = parse_interfaces(INTERFACES, false).unwrap();
+        assert_eq!(result.len(), 1);
+        let status = &result[0];
+        assert_eq!(status.port, 1);
+        assert!(status.up);
+        assert_eq!(status.input_rate_bps, Some(15000));
+        assert_eq!(status.input_rate_pps, Some(20));
+        assert_eq!(status.output_rate_bps, Some(8000));
+        assert_eq!(status.output_rate_pps, Some(10));
+        assert_eq!(status.input_packets, 1234);
+        assert_eq!(status.input_bytes, 567890);
+        assert_eq!(status.input_giants, 2);
+        assert_eq!(status.input_crc, 1);
+        assert_eq!(status.output_packets, 4321);
+
+        /* firmware versions without rate lines */
+        let data = INTERFACES.replace("  5 minute input rate 15000 bits/sec, 20 packets/sec\n", "");
+        let result = parse_interfaces(&data, false).unwrap();
+        assert_eq!(result[0].input_rate_bps, None);
+        assert_eq!(result[0].output_rate_bps, Some(8000));
     }
 
-    #[cfg(feature = "web")]
-    fn http_login(&mut self) -> std::io::Result<(reqwest::blocking::Client, String)> {
-        let client = reqwest::blocking::Client::new();
-        let user = &self.username;
-        let pass = &self.zyxel_password();
-        let dummy = match SystemTime::now().duration_since(SystemTime::UNIX_EPOCH) {
-            Ok(n) => format!("{}000", n.as_secs()),
-            Err(_) => "1000000000000".to_string(),
-        };
-        let url = format!("http://{}/cgi-bin/dispatcher.cgi", self.address);
+    #[test]
+    fn parse_poe_info_reads_typed_supply_state() {
+        let supply = format!("{:<4} {:<5} {:<6} {:<8} {:<15} {:<8} {}", "0", "On", "OK", "120Watts", "30Watts", "12Watts", "108Watts");
+        let data = format!("show power inline consumption\nPower management mode : Class limit mode\n\n{}\n", supply);
+        let (cfg, supplies, ports) = parse_poe_info(&data).unwrap();
+        assert_eq!(cfg.management_mode, PoEMode::Classification);
+        assert!(ports.is_empty());
+        assert_eq!(supplies.len(), 1);
+        assert_eq!(supplies[0].power, SupplyPower::On);
+        assert_eq!(supplies[0].status, SupplyStatus::Ok);
+        assert_eq!(supplies[0].nominal_power, 120);
+        assert_eq!(supplies[0].available_power, 108);
+
+        assert_eq!("Fail".parse::<SupplyStatus>().unwrap(), SupplyStatus::Fault);
+        assert_eq!("Standby".parse::<SupplyPower>().unwrap(), SupplyPower::Other("Standby".to_string()));
+    }
 
-        let authparams = [("login", "1"), ("username", user.as_str()), ("password", pass.as_str()), ("dummy", dummy.as_str())];
-        client.get(url.as_str()).query(&authparams).send().map_err(|e| std::io::Error::new(std::io::ErrorKind::Other, format!("Failed to login: {}", e)))?;
+    #[test]
+    fn check_output_reports_truncated_rows() {
+        assert!(check_output("show mac address-table", MAC_TABLE).is_ok());
 
-        /* Yes, GS1900 series is very crappy */
-        let t = std::time::Duration::from_millis(500);
-        std::thread::sleep(t);
+        let cut = MAC_TABLE.replace("| Static   | gi2\r\n\r\n", "\r\n");
+        match check_output("show mac address-table", &cut) {
+            Err(GS1900Error::Truncated { command, got_rows }) => {
+                assert_eq!(command, "show mac address-table");
+                assert_eq!(got_rows, 6);
+            },
+            x => panic!("unexpected result {:?}", x),
+        }
+    }
 
-        let checkparams = [("login_chk", "1"), ("dummy", dummy.as_str())];
-        let response = client.get(url.as_str()).query(&checkparams).send().map_err(|e| std::io::Error::new(std::io::ErrorKind::Other, format!("Failed to check login: {}", e)))?;
-        let data = response.text().map_err(|e| std::io::Error::new(std::io::ErrorKind::Other, format!("Failed to decode check login data: {}", e)))?;
+    #[test]
+    fn fiber_info_unit_helpers() {
+        let data = "Port | Temperature | Voltage | Current | Output Power | Input Power | OE-Present | LOS\n\
+                    \x20    | (C)         | (V)     | (mA)    | (mW)         | (mW)        |            |\n\
+                    \x20  25 | 35.50  (OK)  | 3.30  (OK) | 6.50  (OK) | 0.25  (OK) | 0.00  (E) | Insert | Normal\n";
+        let result = parse_fiber_info(data).unwrap();
+        assert_eq!(result.len(), 1);
+        let fi = &result[0];
+        assert_eq!(fi.port, 25);
+        assert!(fi.present);
+        assert!((fi.temperature_celsius() - 35.5).abs() < 0.001);
+        assert!((fi.voltage_volts() - 3.3).abs() < 0.001);
+        assert!((fi.current_milliamps() - 6.5).abs() < 0.001);
+        assert!((fi.tx_power_dbm() - -6.0206).abs() < 0.001);
+        assert_eq!(fi.rx_power_dbm(), f32::NEG_INFINITY);
+        assert!(matches!(fi.input_power_status, SFPStatus::Error));
+    }
 
-        if data != "\nOK\n" {
-            return Err(std::io::Error::new(std::io::ErrorKind::Other, "HTTP Login failed!"));
-        }
+    #[test]
+    fn parse_interfaces_splits_flow_control() {
+        let result = parse_interfaces(INTERFACES, false).unwrap();
+        assert!(result[0].flow_control_configured);
+        assert!(!result[0].flow_control_active);
+
+        let data = INTERFACES.replace("flow-control is on (negotiated off)", "flow-control is on (negotiated on)");
+        let result = parse_interfaces(&data, false).unwrap();
+        assert!(result[0].flow_control_active);
+
+        /* without a negotiation result the configured state applies */
+        let data = INTERFACES.replace("flow-control is on (negotiated off)", "flow-control is off");
+        let result = parse_interfaces(&data, false).unwrap();
+        assert!(!result[0].flow_control_configured);
+        assert!(!result[0].flow_control_active);
+    }
 
-        let ssidparams = [("cmd", "1")];
-        let response = client.get(url.as_str()).query(&ssidparams).send().map_err(|e| std::io::Error::new(std::io::ErrorKind::Other, format!("Failed to get session: {}", e)))?;
-        let data = response.text().map_err(|e| std::io::Error::new(std::io::ErrorKind::Other, format!("Failed to decode get session data: {}", e)))?;
+    #[test]
+    fn parse_ping_reads_summary() {
+        /* the chunks passed to ping_progress() add up to this output */
+        let chunks = [
+            "ping 192.168.1.1 count 2\r\nPING 192.168.1.1 (192.168.1.1): 56 data bytes\r\n",
+            "64 bytes from 192.168.1.1: icmp_seq=0 ttl=64 time=0.6 ms\r\n",
+            "64 bytes from 192.168.1.1: icmp_seq=1 ttl=64 time=1.4 ms\r\n\r\n--- 192.168.1.1 ping statistics ---\r\n",
+            "2 packets transmitted, 2 packets received, 0% packet loss\r\nround-trip min/avg/max = 0.6/1.0/1.4 ms\r\n",
+        ];
+        let result = parse_ping(&chunks.concat()).unwrap();
+        assert_eq!(result.transmitted, 2);
+        assert_eq!(result.received, 2);
+        assert!((result.min_ms - 0.6).abs() < 0.001);
+        assert!((result.avg_ms - 1.0).abs() < 0.001);
+        assert!((result.max_ms - 1.4).abs() < 0.001);
+    }
 
-        lazy_static! {
-            static ref RE: Regex = Regex::new(r"setCookie\(.XSSID., .(.*?).\);").unwrap();
-        }
+    #[test]
+    fn find_prompt_accepts_user_and_privileged_mode() {
+        assert_eq!(find_prompt("\x1b[H\x1b[J\x00switch>"), Some("switch>".to_string()));
+        assert_eq!(find_prompt("Welcome\r\n\r\nswitch# "), Some("switch# ".to_string()));
+        assert_eq!(find_prompt("enable\r\nPassword:"), Some("Password:".to_string()));
+        assert_eq!(find_prompt("Welcome\r\n"), None);
+        assert_eq!(find_prompt("switch# show info\r\n"), None);
+    }
 
-        for cap in RE.captures_iter(data.as_str()) {
-            return Ok((client, cap[1].to_string()));
-        }
+    #[test]
+    fn port_range_roundtrip() {
+        let range: PortRange = "1-4,7,9-10".parse().unwrap();
+        assert_eq!(range.ports, vec![1, 2, 3, 4, 7, 9, 10]);
+        assert_eq!(range.to_string(), "1-4,7,9-10");
 
-        Err(std::io::Error::new(std::io::ErrorKind::Other, "Session not found!"))
+        assert!("1-300".parse::<PortRange>().is_err());
+        assert!("4-1".parse::<PortRange>().is_err());
+        assert!("gi1".parse::<PortRange>().is_err());
     }
 
-    #[cfg(feature = "web")]
-    fn construct_headers(&self, session: String) -> reqwest::header::HeaderMap {
-        let mut headers = reqwest::header::HeaderMap::new();
-        headers.insert(reqwest::header::USER_AGENT, reqwest::header::HeaderValue::from_static("reqwest"));
-        headers.insert(reqwest::header::COOKIE, reqwest::header::HeaderValue::from_str(format!("XSSID={}", session).as_str()).unwrap());
-        headers
+    #[test]
+    fn mac_address_formats() {
+        let expected = MacAddress { bytes: [0x00, 0x1a, 0x2b, 0x3c, 0x4d, 0xef] };
+        assert_eq!("00:1a:2b:3c:4d:ef".parse::<MacAddress>().unwrap(), expected);
+        assert_eq!("00-1A-2B-3C-4D-EF".parse::<MacAddress>().unwrap(), expected);
+        assert_eq!("001a.2b3c.4def".parse::<MacAddress>().unwrap(), expected);
+        assert_eq!(expected.to_string(), "00:1a:2b:3c:4d:ef");
+
+        for invalid in &["", "00:1a:2b:3c:4d", "00:1a:2b:3c:4d:ef:01", "0:1a:2b:3c:4d:ef", "00:1a:2b:3c:4d:eg",
+                         "001a.2b3c", "001a.2b3c.4de", "001a.2b\u{e9}.4def", "00-1a:2b-3c:4d-ef", "+0:1a:2b:3c:4d:ef"] {
+            assert!(invalid.parse::<MacAddress>().is_err(), "{:?} was accepted", invalid);
+        }
     }
 
-    #[cfg(feature = "web")]
-    fn http_command(&mut self, client: reqwest::blocking::Client, session: String, params: std::collections::HashMap<&str, &str>) -> std::io::Result<()> {
-        let url = format!("http://{}/cgi-bin/dispatcher.cgi", self.address);
-        let headers = self.construct_headers(session.clone());
+    #[test]
+    fn ipv4_std_roundtrip() {
+        let std_addr = std::net::Ipv4Addr::new(192, 168, 1, 254);
+        let addr = IPv4Address::from(std_addr);
+        assert_eq!(addr.bytes, [192, 168, 1, 254]);
+        assert_eq!(std::net::Ipv4Addr::from(addr), std_addr);
 
-        let request = client.post(url.as_str()).form(&params).headers(headers);
+        let addr: IPv4Address = "10.0.0.1".parse().unwrap();
+        assert_eq!(addr.to_std().to_string(), "10.0.0.1");
+    }
 
-        let _response = request.send();
+    #[test]
+    fn netmask_prefix_len() {
+        let mask = |s: &str| s.parse::<IPv4Address>().unwrap();
+        assert_eq!(mask("0.0.0.0").prefix_len(), Some(0));
+        assert_eq!(mask("255.255.255.0").prefix_len(), Some(24));
+        assert_eq!(mask("255.255.255.255").prefix_len(), Some(32));
+        assert_eq!(mask("255.0.255.0").prefix_len(), None);
+        assert!(!mask("0.255.255.255").is_valid_netmask());
+
+        assert_eq!(IPv4Address::netmask(0).unwrap().bytes, [0, 0, 0, 0]);
+        assert_eq!(IPv4Address::netmask(20).unwrap().bytes, [255, 255, 240, 0]);
+        assert_eq!(IPv4Address::netmask(32).unwrap().bytes, [255, 255, 255, 255]);
+        assert!(IPv4Address::netmask(33).is_err());
+    }
 
-        /*
-         * GS1900 response does not contain an empty line after headers,
-         * which results in an error in the hyper crate (library used by
-         * reqwest to parse the server response). Fortunately we do not
-         * really need the response, so let's just ignore the result.
-         * If hyper crate gets a workaround for the issue, we should check
-         * the HTTP response for success.
-         */
-        //let data = _response.unwrap().text().unwrap();
+    #[test]
+    fn parse_mac_table_accepts_large_vlan_ids() {
+        let result = parse_mac_table(MAC_TABLE).unwrap();
+        assert_eq!(result.len(), 2);
+        assert_eq!(result[1].vlan_id, 3000);
+        assert_eq!(result[1].mac_address, "00:11:22:33:44:66".parse().unwrap());
+        assert_eq!(result[1].entry_type, MacEntryType::Static);
+        assert_eq!(result[1].ports, "gi2");
 
-        Ok(())
+        assert!(parse_mac_table("VID | MAC Address | Type | Ports\n70000 | 00:11:22:33:44:66 | Static | gi2\n").is_err());
     }
 
-    #[cfg(feature = "web")]
-    pub fn control_poe(&mut self, port: u8, state: bool, priority: PoEPriority, power_mode: PoEPowerMode, range_detection: bool, power_limit_mode: PoELimitMode, power_limit: i32) -> std::io::Result<()> {
-        let (client, session) = self.http_login()?;
-
-        let stateparam = match state {
-            true => "1",
-            false => "0",
-        };
-        let prioparam = match priority {
-            PoEPriority::Critical => "0",
-            PoEPriority::High => "1",
-            PoEPriority::Medium => "2",
-            PoEPriority::Low => "3",
-        };
-        let rangeparam = match range_detection {
-            true => "1",
-            false => "0",
+    #[test]
+    fn parse_port_list_skips_lags() {
+        assert_eq!(parse_port_list("gi1-gi4,gi7").unwrap(), vec![1, 2, 3, 4, 7]);
+        assert_eq!(parse_port_list("7,1-2,Trunk1-2").unwrap(), vec![1, 2, 7]);
+        assert_eq!(parse_port_list("---").unwrap(), Vec::<u8>::new());
+        assert!(parse_port_list("gi1-te2").is_err());
+        assert!(parse_port_list("xe1").is_err());
+
+        let members: VLANMembers = "1-2,Trunk1-2,LAG4".parse().unwrap();
+        assert_eq!(members.lags, vec![1, 2, 4]);
+
+        let vlan = VLANInfo {
+            id: 10,
+            name: "office".to_string(),
+            ports_untagged: "1-2".to_string(),
+            ports_tagged: "bogus".to_string(),
+            members_untagged: "1-2".parse().ok(),
+            members_tagged: "bogus".parse().ok(),
+            vlan_type: VLANType::Static,
         };
-        let portparam = format!("{}", port);
+        assert_eq!(vlan.ports_untagged_list(), vec![1, 2]);
+        assert_eq!(vlan.ports_tagged_list(), Vec::<u8>::new());
+    }
 
-        let modeparam = match power_limit_mode {
-            PoELimitMode::Classification => "0",
-            PoELimitMode::User => "0",
-        };
-        if power_limit < 1000 || power_limit > 33000 { /* mW */
-            return Err(std::io::Error::new(std::io::ErrorKind::Other, "Invalid power limit!"));
+    #[test]
+    fn connect_gives_up_after_timeout() {
+        /* accepts TCP connections but never sends an SSH banner */
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let mut config = ConnectionConfig::new("admin", "1234");
+        config.ssh_port = listener.local_addr().unwrap().port();
+        config.connect_timeout = std::time::Duration::from_millis(300);
+
+        let start = std::time::Instant::now();
+        assert!(GS1900::connect("127.0.0.1", &config).is_err());
+        assert!(start.elapsed() >= config.connect_timeout);
+        assert!(start.elapsed() < std::time::Duration::from_secs(5));
+    }
+
+    #[test]
+    fn quote_arg_rejects_control_characters() {
+        assert_eq!(quote_arg("office").unwrap(), "office");
+        assert_eq!(quote_arg("rack 2").unwrap(), "\"rack 2\"");
+        for value in &["", "a\"b", "a\nreboot", "a\rb", "a\x1b[2Jb", "a\tb"] {
+            assert!(quote_arg(value).is_err(), "{:?} was accepted", value);
         }
-        let pwrlimitparam = format!("{}", power_limit);
 
-        let pwrmodeparam = match power_mode {
-            PoEPowerMode::IEEE_802_3af => "0",
-            PoEPowerMode::Legacy => "1",
-            PoEPowerMode::Pre_802_3at => "2",
-            PoEPowerMode::IEEE_802_3at => "3",
-        };
+        assert!(check_command("show vlan 10").is_ok());
+        for cmd in &["show info\nreboot", "show info\r", "show info\x03", "show\x1b[Ainfo"] {
+            assert!(check_command(cmd).is_err(), "{:?} was accepted", cmd);
+        }
+    }
 
-        let mut params = std::collections::HashMap::new();
-        params.insert("cmd", "775");
-        params.insert("portlist", portparam.as_str());
-        params.insert("state", stateparam);
-        params.insert("portPriority", prioparam);
-        params.insert("portPowerMode", pwrmodeparam);
-        params.insert("portRangeDetection", rangeparam);
-        params.insert("portLimitMode", modeparam);
-        params.insert("portPowerLimit", pwrlimitparam.as_str());
-        params.insert("poeTimeRange", "20");
-        params.insert("sysSubmit", "Apply");
-        params.insert("XSSID", session.as_str());
+    #[test]
+    fn port_range_collapses_consecutive_ports() {
+        assert_eq!(PortRange::new(&[5, 1, 2, 3, 3]).to_string(), "1-3,5");
+        assert_eq!(PortRange::new(&[1, 2, 3, 4, 7, 8, 10]).to_string(), "1-4,7-8,10");
+        assert_eq!(PortRange::new(&[9]).to_string(), "9");
+        assert_eq!(PortRange::new(&[254, 255]).to_string(), "254-255");
+        assert_eq!(PortRange::new(&[]).to_string(), "");
+    }
 
-        self.http_command(client, session.clone(), params)
+    #[test]
+    fn firmware_version_parsing() {
+        let version: FirmwareVersion = "V2.70(AAHL.3) | 12/10/2021".parse().unwrap();
+        assert_eq!((version.major, version.minor, version.build), (2, 70, 3));
+        assert_eq!(version.model_code, "AAHL");
+        assert_eq!(version.raw, "V2.70(AAHL.3) | 12/10/2021");
+        assert_eq!(version.to_string(), "V2.70(AAHL.3)");
+
+        let older: FirmwareVersion = "V2.60(AAHL.0)".parse().unwrap();
+        assert!(older < version);
+        assert!("V2.70(AAHL.4)".parse::<FirmwareVersion>().unwrap() > version);
+
+        /* boot loader versions have no model code */
+        let boot: FirmwareVersion = "V2.00 | 05/31/2016".parse().unwrap();
+        assert_eq!((boot.major, boot.minor, boot.build), (2, 0, 0));
+        assert_eq!(boot.model_code, "");
+        assert_eq!(boot.to_string(), "V2.00");
+
+        for raw in &["", "2.70(AAHL.3)", "V2.70b", "V2.70(AAHL)", "V2.x(AAHL.3)", "V99999.0(AAHL.3)"] {
+            assert!(raw.parse::<FirmwareVersion>().is_err(), "{:?} was accepted", raw);
+        }
+
+        /* basic_info keeps unknown formats as raw strings */
+        let unknown = FirmwareVersion { raw: "4.80(ABCD.1)b2".to_string(), ..Default::default() };
+        assert_eq!(unknown.to_string(), "4.80(ABCD.1)b2");
+        assert_ne!(unknown, FirmwareVersion { raw: "4.80(ABCD.2)b1".to_string(), ..Default::default() });
+        assert_eq!(unknown, FirmwareVersion { raw: "4.80(ABCD.1)b2".to_string(), ..Default::default() });
+        assert!(unknown < older);
     }
 
-    #[cfg(feature = "web")]
-    pub fn control_port(&mut self, port: u8, label: String, enabled: bool, speed: PortSpeed, duplex: PortDuplex, flow_control: bool) -> std::io::Result<()> {
-        let (client, session) = self.http_login()?;
+    #[test]
+    fn parse_switchports_keeps_unknown_modes() {
+        let data = "Port : gi1
+Port Mode : Access
+Ingress Filtering : enabled
+Ingress UnTagged VLAN ( NATIVE ) : 10
+
+Port : gi2
+Port Mode : Dot1q-tunnel
+Ingress UnTagged VLAN ( NATIVE ) : 20
+
+Port : gi3
+Port Mode : Trunk
+Ingress UnTagged VLAN ( NATIVE ) : 1
+Trunking VLANs Enabled / Allowed VLAN : 1-3
+";
+        let result = parse_switchports(data).unwrap();
+        assert_eq!(result.len(), 3);
+        assert!(matches!(result[&1].mode, Some(PortMode::Access)));
+        assert_eq!(result[&1].pvid, 10);
+        assert!(result[&2].mode.is_none());
+        assert_eq!(result[&2].pvid, 20);
+        assert!(matches!(result[&3].mode, Some(PortMode::Trunk)));
+        assert!(result[&3].allowed_vlans.is_some());
+    }
 
-        let portparam = format!("{}", port);
+    /// Shell replaying output chunks, each arriving the given time after the previous read
+    struct ScriptedShell {
+        chunks: std::collections::VecDeque<(u64, &'static str)>,
+        keys: usize,
+    }
 
-        let stateparam = match enabled {
-            true => "1",
-            false => "0",
-        };
+    impl ScriptedShell {
+        fn new(chunks: &[(u64, &'static str)]) -> ScriptedShell {
+            ScriptedShell { chunks: chunks.iter().cloned().collect(), keys: 0 }
+        }
+    }
 
-        let speedparam: &str;
-        if speed.auto {
-            speedparam = "0";
-        } else if speed.speed >= 1000 {
-            speedparam = "3";
-        } else if speed.speed >= 100 {
-            speedparam = "2";
-        } else if speed.speed >= 10 {
-            speedparam = "1";
-        } else {
-            speedparam = "0";
+    impl Shell for ScriptedShell {
+        fn read_timeout(&mut self, buffer: &mut [u8], timeout: std::time::Duration) -> std::io::Result<usize> {
+            match self.chunks.front() {
+                Some((delay, _)) if std::time::Duration::from_millis(*delay) <= timeout => {},
+                _ => return Err(std::io::ErrorKind::TimedOut.into()),
+            }
+            let (_, chunk) = self.chunks.pop_front().unwrap();
+            let len = chunk.len().min(buffer.len());
+            buffer[..len].copy_from_slice(&chunk.as_bytes()[..len]);
+            if len < chunk.len() {
+                self.chunks.push_front((0, &chunk[len..]));
+            }
+            Ok(len)
         }
 
-        let duplexparam = match duplex {
-            PortDuplex::Auto => "0",
-            PortDuplex::Full => "1",
-            PortDuplex::Half => "2",
-        };
+        fn write_all(&mut self, data: &[u8]) -> std::io::Result<()> {
+            assert_eq!(data, b" ");
+            self.keys += 1;
+            Ok(())
+        }
 
-        let fcparam = match flow_control {
-            true => "1",
-            false => "0",
-        };
+        fn eof(&self) -> bool {
+            self.chunks.is_empty()
+        }
+    }
 
-        let mut params = std::collections::HashMap::new();
-        params.insert("cmd", "770");
-        params.insert("portlist", portparam.as_str());
-        params.insert("descp", label.as_str());
-        params.insert("state", stateparam);
-        params.insert("speed", speedparam);
-        params.insert("duplex", duplexparam);
-        params.insert("fc", fcparam);
-        params.insert("sysSubmit", "Apply");
-        params.insert("XSSID", session.as_str());
+    const BANNER_CONFIG: &[(u64, &str)] = &[
+        (0, "show running-config\r\n!\r\nbanner motd ^\r\nswitch#"),
+        (150, "\r\n^\r\nhostname switch\r\n"),
+        (0, "switch# "),
+    ];
+
+    #[test]
+    fn read_output_waits_after_prompt_lookalike() {
+        let timeout = std::time::Duration::from_secs(1);
+
+        /* the rows after the banner line arrive within the grace period */
+        let mut shell = ScriptedShell::new(BANNER_CONFIG);
+        let data = read_output(&mut shell, "switch# ", "show running-config", timeout, &mut |_| {}).unwrap();
+        assert!(data.contains("hostname switch"));
+        assert!(data.ends_with("switch# "));
+        assert!(shell.chunks.is_empty());
+
+        /* the pager continues after a key press */
+        let mut shell = ScriptedShell::new(&[(0, "show vlan\r\n1 | default\r\n--More--"), (500, "\r\n2 | office\r\nswitch# ")]);
+        let data = read_output(&mut shell, "switch# ", "show vlan", timeout, &mut |_| {}).unwrap();
+        assert!(data.contains("2 | office"));
+        assert_eq!(shell.keys, 1);
+
+        /* no prompt */
+        let mut shell = ScriptedShell::new(&[(0, "show vlan\r\n1 | default\r\n")]);
+        assert!(matches!(read_output(&mut shell, "switch# ", "show vlan", timeout, &mut |_| {}), Err(GS1900Error::Timeout { .. })));
+    }
+
+    #[test]
+    fn is_show_command_accepts_abbreviations() {
+        assert!(is_show_command("show vlan"));
+        assert!(is_show_command("  sh int gi1 status"));
+        assert!(!is_show_command("s"));
+        assert!(!is_show_command("configure"));
+        assert!(!is_show_command("shutdown"));
+        assert!(!is_show_command("showx"));
+        assert!(!is_show_command(""));
+    }
 
-        println!("{:?}", params);
+    #[test]
+    fn interface_counters_exceed_32_bits() {
+        let data = INTERFACES.replace("567890 bytes", "5000000000 bytes");
+        let old = parse_interfaces(INTERFACES, false).unwrap()[0];
+        let new = parse_interfaces(&data, false).unwrap()[0];
+        assert_eq!(new.input_bytes, 5_000_000_000);
 
-        self.http_command(client, session.clone(), params)
+        let delta = new.counter_delta(&old);
+        assert_eq!(delta.input_bytes, 5_000_000_000 - 567890);
+        assert_eq!(delta.input_packets, 0);
+    }
+
+    #[test]
+    fn read_mode_prompt_follows_hostname_change() {
+        let timeout = std::time::Duration::from_secs(1);
+
+        let mut shell = ScriptedShell::new(&[(0, "configure\r\nswitch(config)# ")]);
+        let (_, prompt) = read_mode_prompt(&mut shell, "configure", timeout).unwrap();
+        assert_eq!(prompt, "switch(config)# ");
+
+        /* the new hostname shows up in the prompt right after the command */
+        let mut shell = ScriptedShell::new(&[(0, "hostname lab1\r\n"), (50, "lab1(config)# ")]);
+        let (_, prompt) = read_mode_prompt(&mut shell, "hostname lab1", timeout).unwrap();
+        assert_eq!(prompt, "lab1(config)# ");
+
+        let mut shell = ScriptedShell::new(&[(0, "end\r\nlab1# ")]);
+        let (data, prompt) = read_mode_prompt(&mut shell, "end", timeout).unwrap();
+        assert_eq!(prompt, "lab1# ");
+        assert!(!is_prompt(data.rsplit('\n').next().unwrap(), "switch# "));
+        assert!(is_prompt(data.rsplit('\n').next().unwrap(), &prompt));
+
+        /* a line ending with '#' followed by more output is not the prompt */
+        let mut shell = ScriptedShell::new(&[(0, "banner motd #"), (100, "\r\nswitch(config)# ")]);
+        let (_, prompt) = read_mode_prompt(&mut shell, "banner motd #", timeout).unwrap();
+        assert_eq!(prompt, "switch(config)# ");
     }
 }
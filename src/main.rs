@@ -6,10 +6,13 @@ use std::str::FromStr;
 
 fn help(name: &str) {
         eprintln!("{} <address> <user> <pass> <cmd>", name);
+        eprintln!("{} <address> <cmd> (with GS1900_USER and GS1900_PASS set)", name);
         eprintln!("");
         eprintln!("Commands:");
         eprintln!(" basic-info");
+        eprintln!(" reboot-cause");
         eprintln!(" lldp-info");
+        eprintln!(" lldp-config");
         eprintln!(" fiber-info");
         eprintln!(" poe-info");
         eprintln!(" poe-debug");
@@ -18,10 +21,54 @@ fn help(name: &str) {
         eprintln!(" vlan-info");
         eprintln!(" mac-table");
         eprintln!(" mac-table-port <port>");
+        eprintln!(" mac-table-vlan <vlan>");
         eprintln!(" cable-info-port <port>");
+        eprintln!(" cable-info-ports <ports>");
         eprintln!(" interface-info-port <port>");
+        eprintln!(" storm-rates");
+        eprintln!(" interface-utilization");
+        eprintln!(" jumbo-diagnosis <port>");
+        eprintln!(" queue-stats <port>");
+        eprintln!(" locate-port <port>");
+        eprintln!(" fiber-counters <port>");
+        eprintln!(" port-config <port>");
         eprintln!(" lookup-mac-address <MAC>");
+        eprintln!(" ping <IP>");
         eprintln!(" interface-status-info");
+        eprintln!(" interface-status-info-port <port>");
+        eprintln!(" port-map");
+        eprintln!(" topology");
+        eprintln!(" port-autoneg");
+        eprintln!(" port-descriptions");
+        eprintln!(" dns-info");
+        eprintln!(" ip-interfaces");
+        eprintln!(" static-routes");
+        eprintln!(" post-results");
+        eprintln!(" syslog-info");
+        eprintln!(" email-alert-info");
+        eprintln!(" alarms");
+        eprintln!(" thermal-events");
+        eprintln!(" poe-events");
+        eprintln!(" acl-rules");
+        eprintln!(" protection-info");
+        eprintln!(" port-priority");
+        eprintln!(" stp-mode");
+        eprintln!(" stp-port-config");
+        eprintln!(" stp-port-stats");
+        eprintln!(" management-acl");
+        eprintln!(" gvrp-info");
+        eprintln!(" trunk-allowed-vlans");
+        eprintln!(" dhcp-snooping-ports");
+        eprintln!(" security-violations");
+        eprintln!(" timezone");
+        eprintln!(" services");
+        eprintln!(" led-mode");
+        eprintln!(" mirror-capacity");
+        eprintln!(" flap-dampening");
+        eprintln!(" clear-counters [port]");
+        eprintln!(" flush-mac [port]");
+        eprintln!(" save-config");
+        eprintln!(" reboot");
         #[cfg(feature = "web")]
         eprintln!("");
         #[cfg(feature = "web")]
@@ -37,6 +84,14 @@ fn help(name: &str) {
 
 }
 
+/* optional port argument, an empty argument selects all ports */
+fn port_arg(arg: &str) -> std::io::Result<Option<u8>> {
+    if arg.is_empty() {
+        return Ok(None);
+    }
+    arg.parse().map(Some).map_err(|_| std::io::Error::new(std::io::ErrorKind::InvalidInput, format!("Invalid port {}", arg)))
+}
+
 fn main_err() -> std::io::Result<()> {
     println!("Zyxel GS1900 Tool");
     println!();
@@ -47,25 +102,31 @@ fn main_err() -> std::io::Result<()> {
         return Err(std::io::Error::new(std::io::ErrorKind::Other, "Not enough parameters"));
     }
 
-    if args.len() < 5 {
+    /* credentials are taken from GS1900_USER / GS1900_PASS if omitted */
+    let env_auth = args.len() == 3 || args.len() == 4;
+
+    if args.len() < 5 && !env_auth {
         help(args[0].as_str());
         eprintln!("");
         return Err(std::io::Error::new(std::io::ErrorKind::Other, "Not enough parameters"));
     }
 
     let addr = args[1].to_string();
-    let user = args[2].to_string();
-    let pw = args[3].to_string();
-    let cmd = args[4].as_str();
+    let cmdpos = if env_auth { 2 } else { 4 };
+    let cmd = args[cmdpos].as_str();
     let arg : String;
-    if args.len() > 5 {
-        arg = args[5].to_string();
+    if args.len() > cmdpos + 1 {
+        arg = args[cmdpos + 1].to_string();
     } else {
         arg = "".to_string();
     }
 
     println!("Connect to {}...", addr);
-    let mut sw = gs1900::GS1900::new(addr, user, pw)?;
+    let mut sw = if env_auth {
+        gs1900::GS1900::from_env(&addr)?
+    } else {
+        gs1900::GS1900::new(addr, args[2].to_string(), args[3].to_string())?
+    };
 
     match cmd {
         "basic-info" => {
@@ -73,6 +134,16 @@ fn main_err() -> std::io::Result<()> {
             let data = sw.basic_info()?;
             println!("{:?}", data);
         },
+        "reboot-cause" => {
+            println!("Requesting reboot cause...");
+            let data = sw.last_reboot()?;
+            println!("{:?}", data);
+        },
+        "lldp-config" => {
+            println!("Requesting LLDP config...");
+            let data = sw.lldp_config()?;
+            println!("{:?}", data);
+        },
         "lldp-info" => {
             println!("Requesting LLDP info...");
             let data = sw.lldp_info()?;
@@ -83,7 +154,9 @@ fn main_err() -> std::io::Result<()> {
         "fiber-info" => {
             println!("Requesting fiber info...");
             let data = sw.fiber_info()?;
-            println!("{:?}", data);
+            for entry in data {
+                println!("{:?}", entry);
+            }
         },
         "poe-info" => {
             println!("Requesting PoE info...");
@@ -93,7 +166,9 @@ fn main_err() -> std::io::Result<()> {
         "poe-debug" => {
             println!("Requesting PoE debug info...");
             let data = sw.poe_debug()?;
-            println!("{:?}", data);
+            for entry in data {
+                println!("{:?}", entry);
+            }
         },
         "cable-info" => {
             println!("Requesting cable info...");
@@ -102,6 +177,14 @@ fn main_err() -> std::io::Result<()> {
                 println!("{:?}", x);
             }
         },
+        "cable-info-ports" => {
+            println!("Requesting cable info...");
+            let ports: gs1900::PortRange = arg.parse()?;
+            let data = sw.cable_info_ports(&ports.ports)?;
+            for x in data {
+                println!("{:?}", x);
+            }
+        },
         "cable-info-port" => {
             println!("Requesting cable info...");
             let data = sw.cable_info_port(arg.parse().unwrap())?;
@@ -121,6 +204,51 @@ fn main_err() -> std::io::Result<()> {
             let data = sw.interface_info_port(arg.parse().unwrap())?;
             println!("{:?}", data);
         },
+        "interface-utilization" => {
+            println!("Requesting interface utilization...");
+            let data = sw.interface_utilization()?;
+            for x in data {
+                println!("{:?}", x);
+            }
+        },
+        "jumbo-diagnosis" => {
+            println!("Requesting jumbo frame diagnosis...");
+            let data = sw.jumbo_diagnosis(arg.parse().unwrap())?;
+            println!("{:?}", data);
+        },
+        "storm-rates" => {
+            println!("Measuring broadcast/multicast rates...");
+            let data = sw.storm_rates(std::time::Duration::from_secs(5))?;
+            for x in data {
+                println!("{:?}", x);
+            }
+        },
+        "queue-stats" => {
+            println!("Requesting queue statistics...");
+            let data = sw.queue_stats(arg.parse().unwrap())?;
+            for x in data {
+                println!("{:?}", x);
+            }
+        },
+        "fiber-counters" => {
+            println!("Requesting fiber counters...");
+            let data = sw.fiber_counters(arg.parse().unwrap())?;
+            println!("{:?}", data);
+        },
+        "port-config" => {
+            println!("Requesting port configuration...");
+            let data = sw.port_config(arg.parse().unwrap())?;
+            println!("{}", data);
+        },
+        "locate-port" => {
+            println!("Blinking port LED...");
+            sw.locate_port(arg.parse().unwrap(), std::time::Duration::from_secs(30))?;
+        },
+        "interface-status-info-port" => {
+            println!("Requesting interface port status...");
+            let data = sw.interface_status_info_port(arg.parse().unwrap())?;
+            println!("{:?}", data);
+        },
         "interface-status-info" => {
             println!("Requesting interface status info...");
             let data = sw.interface_status_info()?;
@@ -128,6 +256,185 @@ fn main_err() -> std::io::Result<()> {
                 println!("{:?}", x);
             }
         },
+        "port-map" => {
+            println!("Requesting port map...");
+            let data = sw.port_map()?;
+            for x in data {
+                println!("{:?}", x);
+            }
+        },
+        "topology" => {
+            println!("Requesting topology...");
+            let data = sw.topology()?;
+            for x in data {
+                println!("{:?}", x);
+            }
+        },
+        "port-autoneg" => {
+            println!("Requesting auto-negotiation info...");
+            let data = sw.port_autoneg()?;
+            for x in data {
+                println!("{:?}", x);
+            }
+        },
+        "port-descriptions" => {
+            println!("Requesting port descriptions...");
+            let data = sw.port_descriptions()?;
+            println!("{:?}", data);
+        },
+        "ip-interfaces" => {
+            println!("Requesting IP interfaces...");
+            let data = sw.ip_interfaces()?;
+            for x in data {
+                println!("{:?}", x);
+            }
+        },
+        "static-routes" => {
+            println!("Requesting static routes...");
+            let data = sw.static_routes()?;
+            for x in data {
+                println!("{:?}", x);
+            }
+        },
+        "dns-info" => {
+            println!("Requesting DNS info...");
+            let data = sw.dns_config()?;
+            println!("{:?}", data);
+        },
+        "post-results" => {
+            println!("Requesting POST results...");
+            let data = sw.post_results()?;
+            for x in data {
+                println!("{:?}", x);
+            }
+        },
+        "syslog-info" => {
+            println!("Requesting syslog info...");
+            let data = sw.syslog_config()?;
+            println!("{:?}", data);
+        },
+        "email-alert-info" => {
+            println!("Requesting email alert info...");
+            let data = sw.email_alert_config()?;
+            println!("{:?}", data);
+        },
+        "poe-events" => {
+            println!("Requesting PoE events...");
+            let data = sw.poe_events()?;
+            for x in data {
+                println!("{:?}", x);
+            }
+        },
+        "thermal-events" => {
+            println!("Requesting thermal events...");
+            let data = sw.thermal_events()?;
+            for x in data {
+                println!("{:?}", x);
+            }
+        },
+        "alarms" => {
+            println!("Requesting alarms...");
+            let data = sw.alarms()?;
+            for x in data {
+                println!("{:?}", x);
+            }
+        },
+        "acl-rules" => {
+            println!("Requesting ACL rules...");
+            let data = sw.acl_rules()?;
+            for x in data {
+                println!("{:?}", x);
+            }
+        },
+        "protection-info" => {
+            println!("Requesting protection settings...");
+            let data = sw.protection_config()?;
+            println!("{:?}", data);
+        },
+        "port-priority" => {
+            println!("Requesting port priorities...");
+            let data = sw.port_priority()?;
+            for x in data {
+                println!("{:?}", x);
+            }
+        },
+        "stp-mode" => {
+            println!("Requesting STP mode...");
+            let data = sw.stp_mode()?;
+            println!("{:?}", data);
+        },
+        "stp-port-config" => {
+            println!("Requesting STP port config...");
+            let data = sw.stp_port_config()?;
+            for x in data {
+                println!("{:?}", x);
+            }
+        },
+        "security-violations" => {
+            println!("Requesting security violation counters...");
+            let data = sw.security_violations()?;
+            println!("{:?}", data);
+        },
+        "management-acl" => {
+            println!("Requesting management ACL...");
+            let data = sw.management_acl()?;
+            for x in data {
+                println!("{:?}", x);
+            }
+        },
+        "gvrp-info" => {
+            println!("Requesting GVRP info...");
+            let data = sw.gvrp_config()?;
+            println!("{:?}", data);
+        },
+        "trunk-allowed-vlans" => {
+            println!("Requesting trunk allowed VLANs...");
+            let data = sw.trunk_allowed_vlans()?;
+            for x in data {
+                println!("{:?}", x);
+            }
+        },
+        "dhcp-snooping-ports" => {
+            println!("Requesting DHCP snooping ports...");
+            let data = sw.dhcp_snooping_ports()?;
+            for x in data {
+                println!("{:?}", x);
+            }
+        },
+        "timezone" => {
+            println!("Requesting time zone...");
+            let data = sw.timezone()?;
+            println!("{:?}", data);
+        },
+        "services" => {
+            println!("Requesting management services...");
+            let data = sw.services()?;
+            println!("{:?}", data);
+        },
+        "stp-port-stats" => {
+            println!("Requesting STP port statistics...");
+            let data = sw.stp_port_stats()?;
+            for x in data {
+                println!("{:?}", x);
+            }
+        },
+        "led-mode" => {
+            println!("Requesting LED mode...");
+            let data = sw.led_mode()?;
+            println!("{:?}", data);
+        },
+        "mirror-capacity" => {
+            println!("Requesting mirror capacity...");
+            let data = sw.mirror_capacity()?;
+            println!("{:?}", data);
+        },
+        "flap-dampening" => {
+            println!("Requesting link flap dampening...");
+            let data = sw.flap_dampening()?;
+            for x in data {
+                println!("{:?}", x);
+            }
+        },
         "vlan-info" => {
             println!("Requesting VLAN info...");
             let data = sw.vlan_info()?;
@@ -147,11 +454,43 @@ fn main_err() -> std::io::Result<()> {
                 println!("{:?}", x);
             }
         },
+        "mac-table-vlan" => {
+            println!("Requesting MAC table...");
+            let data = sw.mac_table_vlan(arg.parse().unwrap())?;
+            for x in data {
+                println!("{:?}", x);
+            }
+        },
         "lookup-mac-address" => {
             println!("Requesting MAC table...");
             let data = sw.lookup_mac_address(gs1900::MacAddress::from_str(arg.as_str()).unwrap())?;
             println!("{:?}", data);
         },
+        "ping" => {
+            println!("Pinging {}...", arg);
+            let data = sw.ping_progress(gs1900::IPv4Address::from_str(arg.as_str())?, 4, |x| {
+                print!("{}", x);
+                let _ = std::io::Write::flush(&mut std::io::stdout());
+            })?;
+            println!();
+            println!("{:?}", data);
+        },
+        "clear-counters" => {
+            println!("Clearing interface counters...");
+            sw.clear_interface_counters(port_arg(&arg)?)?;
+        },
+        "flush-mac" => {
+            println!("Flushing dynamic MAC addresses...");
+            sw.flush_dynamic_mac(port_arg(&arg)?)?;
+        },
+        "save-config" => {
+            println!("Saving running configuration...");
+            sw.save_config()?;
+        },
+        "reboot" => {
+            println!("Rebooting...");
+            sw.reboot(true)?;
+        },
         #[cfg(feature = "web")]
         "poe-enable" => {
             println!("HTTP request...");